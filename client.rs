@@ -0,0 +1,101 @@
+use ink::{
+    env::{
+        call::{build_call, ExecutionInput, Selector},
+        CallFlags, DefaultEnvironment,
+    },
+    prelude::vec::Vec,
+    primitives::AccountId,
+    selector_bytes,
+};
+
+use crate::{flatten_psp34_result, Id, PSP34CallError, PSP34Result};
+
+/// Handle to a PSP34 contract deployed at `account_id`, for contracts
+/// composing with PSP34 collections (marketplaces, vaults, ...).
+///
+/// Calls are built with ink!'s call builder against the selectors of the
+/// `PSP34` trait, so any contract implementing it can be targeted. Unlike
+/// ink!'s generated contract refs, a call never panics: a callee which
+/// traps or can't dispatch the message yields a `PSP34CallError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct PSP34Ref {
+    account_id: AccountId,
+}
+
+impl From<AccountId> for PSP34Ref {
+    fn from(account_id: AccountId) -> Self {
+        PSP34Ref { account_id }
+    }
+}
+
+impl PSP34Ref {
+    pub fn account_id(&self) -> AccountId {
+        self.account_id
+    }
+
+    pub fn balance_of(&self, owner: AccountId) -> Result<u32, PSP34CallError> {
+        let result = build_call::<DefaultEnvironment>()
+            .call(self.account_id)
+            .exec_input(
+                ExecutionInput::new(Selector::new(selector_bytes!("PSP34::balance_of")))
+                    .push_arg(owner),
+            )
+            .returns::<u32>()
+            .try_invoke()?;
+        Ok(result?)
+    }
+
+    pub fn owner_of(&self, id: Id) -> Result<Option<AccountId>, PSP34CallError> {
+        let result = build_call::<DefaultEnvironment>()
+            .call(self.account_id)
+            .exec_input(
+                ExecutionInput::new(Selector::new(selector_bytes!("PSP34::owner_of"))).push_arg(id),
+            )
+            .returns::<Option<AccountId>>()
+            .try_invoke()?;
+        Ok(result?)
+    }
+
+    pub fn transfer(&self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34CallError> {
+        let result = build_call::<DefaultEnvironment>()
+            .call(self.account_id)
+            .exec_input(
+                ExecutionInput::new(Selector::new(selector_bytes!("PSP34::transfer")))
+                    .push_arg(to)
+                    .push_arg(id)
+                    .push_arg(data),
+            )
+            .returns::<PSP34Result>()
+            .try_invoke()?;
+        flatten_psp34_result(result)
+    }
+
+    /// Calls `PSP34::transfer_from`, allowing the collection to call
+    /// back into the calling contract, so that it can pull tokens into
+    /// itself from collections calling `PSP34Receiver::on_received`.
+    pub fn transfer_from(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+        data: Vec<u8>,
+    ) -> Result<(), PSP34CallError> {
+        let result = build_call::<DefaultEnvironment>()
+            .call(self.account_id)
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .exec_input(
+                ExecutionInput::new(Selector::new(selector_bytes!("PSP34::transfer_from")))
+                    .push_arg(from)
+                    .push_arg(to)
+                    .push_arg(id)
+                    .push_arg(data),
+            )
+            .returns::<PSP34Result>()
+            .try_invoke()?;
+        flatten_psp34_result(result)
+    }
+}
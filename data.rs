@@ -1,22 +1,34 @@
-use crate::PSP34Error;
+use crate::{PSP34Error, PSP34MintHooks, PSP34Result};
 
 use ink::{
-    prelude::{vec, vec::Vec},
+    env::{ecdsa_recover, hash::Blake2x256, hash_bytes, DefaultEnvironment},
+    prelude::{format, string::String, vec, vec::Vec},
     primitives::AccountId,
     storage::Mapping,
 };
 
-use crate::types::{Balance, Id};
+use crate::keys::WellKnownKeys;
+use crate::types::{ApprovalStatus, Balance, Id, PSP34Config, TokenInfo};
+
+use scale::{Decode, Encode};
 
 /// Temporary type for events emitted during operations that change the
 /// state of PSP22Data struct.
 /// This is meant to be replaced with proper ink! events as soon as the
 /// language allows for event definitions outside contracts.
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
 pub enum PSP34Event {
     Transfer {
         from: Option<AccountId>,
         to: Option<AccountId>,
         id: Id,
+        /// Account moving the token on behalf of `from`, `None` when it is
+        /// `from` itself or the collection
+        operator: Option<AccountId>,
     },
     Approval {
         owner: AccountId,
@@ -29,11 +41,69 @@ pub enum PSP34Event {
         key: Vec<u8>,
         data: Vec<u8>,
     },
+    SoldOut {
+        total_supply: Balance,
+    },
+    Revealed {
+        revealed_uri: String,
+    },
+    MintPriceChanged {
+        old: Balance,
+        new: Balance,
+    },
+    AdminTransfer {
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+    },
+    Burn {
+        from: AccountId,
+        id: Id,
+    },
+    Rekeyed {
+        old_id: Id,
+        new_id: Id,
+    },
+    DelegateChanged {
+        delegator: AccountId,
+        from_delegate: Option<AccountId>,
+        to_delegate: AccountId,
+    },
+    DelegateVotesChanged {
+        delegate: AccountId,
+        previous_votes: u128,
+        new_votes: u128,
+    },
 }
 
+/// Default maximum amount of items accepted by batch operations
+pub const DEFAULT_MAX_BATCH_SIZE: u32 = 128;
+
+/// Default maximum length of attribute keys, in bytes
+pub const DEFAULT_MAX_ATTRIBUTE_KEY_LEN: u32 = 64;
+
+/// Default maximum length of attribute values, in bytes
+pub const DEFAULT_MAX_ATTRIBUTE_VALUE_LEN: u32 = 1024;
+
+/// Version of the `PSP34Data` storage layout written by this crate.
+///
+/// It is bumped once per release changing the layout. A contract upgraded
+/// through `set_code_hash` can read `storage_version` from its existing
+/// storage (decoded with the previous layout) and populate the fields
+/// added since that version with their defaults, before writing the data
+/// back with the new layout and `STORAGE_VERSION`.
+pub const STORAGE_VERSION: u16 = 1;
+
 #[ink::storage_item]
-#[derive(Debug, Default)]
+#[derive(Debug)]
+#[allow(clippy::type_complexity)]
 pub struct PSP34Data {
+    /// Version of the layout the data was written with, see `STORAGE_VERSION`
+    pub storage_version: u16,
+
+    /// Id of the collection, derived from the contract's account id if `None`
+    pub collection_id: Option<Id>,
+
     /// Mapping of a token to its owner
     pub tokens_owner: Mapping<Id, AccountId>,
 
@@ -46,17 +116,219 @@ pub struct PSP34Data {
     /// Mapping of approved operators for all the tokens
     pub allowances_all: Mapping<(AccountId, AccountId), bool>,
 
+    /// Mapping of operators allowed to move any token of any owner, set by
+    /// the collection admin rather than by the owners themselves
+    pub global_operators: Mapping<AccountId, bool>,
+
+    /// Whether transfers are frozen collection-wide, mints and burns
+    /// remaining allowed
+    pub transfers_frozen: bool,
+
+    /// Whether operators can delegate their approvals one hop further,
+    /// see `delegate_operator`
+    pub transitive_approvals: bool,
+
+    /// Mapping of an operator to the approved operators that delegated
+    /// their approvals to it
+    pub operator_delegators: Mapping<AccountId, Vec<AccountId>>,
+
+    /// Mapping of an owner and token to the operators approved for it
+    /// Helps with listing per-token operators, as `allowances` can't be iterated
+    pub token_operators: Mapping<(AccountId, Id), Vec<AccountId>>,
+
+    /// Mapping of an owner to the operators approved for all its tokens
+    /// Helps with listing them, as `allowances_all` can't be iterated
+    pub all_operators: Mapping<AccountId, Vec<AccountId>>,
+
+    /// Mapping of an owner and operator to the amount of tokens the owner
+    /// approved the operator for, one by one
+    pub token_approval_counts: Mapping<(AccountId, AccountId), u32>,
+
+    /// Account treated as invalid, tokens can't be sent to it nor can
+    /// it act on tokens. Defaults to the zeroed account
+    pub dead_address: AccountId,
+
     /// Total supply of the collection
     pub total_supply: Balance,
 
+    /// Amount of tokens ever minted, which burns don't decrease
+    pub lifetime_minted: u128,
+
+    /// Lowest `Id::U128` the next automatically assigned id can be, only
+    /// ever going up so that ids of burnt tokens aren't handed out again
+    pub next_id: u128,
+
+    /// Maximum amount of tokens that can exist, unlimited if `None`
+    pub max_supply: Option<Balance>,
+
+    /// Amount of tokens set aside for `reserve_mint`, which public mints
+    /// can't consume
+    pub reserved_supply: Balance,
+
+    /// Amount of tokens an owner must keep when transferring, 0 if none
+    pub min_balance_to_keep: u32,
+
+    /// Chance for `transfer_from` to burn the token instead of delivering
+    /// it, in basis points, see `transfer_burns`
+    pub transfer_burn_bps: u16,
+
+    /// Maximum amount of tokens a single account can hold, unlimited if `None`
+    pub max_balance_per_owner: Option<u32>,
+
+    /// First block minting is allowed at, open-ended if `None`
+    pub mint_start: Option<u64>,
+
+    /// Last block minting is allowed at, open-ended if `None`
+    pub mint_end: Option<u64>,
+
+    /// Minimum amount of blocks between two mints of an account, no
+    /// cooldown if 0
+    pub mint_cooldown_blocks: u64,
+
+    /// Mapping of callers to the block they last minted at, only tracked
+    /// while `mint_cooldown_blocks` is set
+    pub last_mint_block: Mapping<AccountId, u64>,
+
+    /// Mapping of the external ids processed by `mint_idempotent` to the
+    /// token minted for them
+    pub external_mints: Mapping<Vec<u8>, Id>,
+
+    /// Reverse of `external_mints`, so that `rekey` can follow the token
+    pub external_mint_ids: Mapping<Id, Vec<u8>>,
+
+    /// Mapping of owners and nonces of the burn permits already used
+    pub used_burn_nonces: Mapping<(AccountId, u64), bool>,
+
+    /// Mapping of owners and nonces of the approval permits already used
+    pub used_permit_nonces: Mapping<(AccountId, u64), bool>,
+
+    /// Mapping of the hashes of the mint vouchers already redeemed
+    pub used_vouchers: Mapping<[u8; 32], bool>,
+
+    /// Whether minting is restricted to allowlisted accounts
+    pub allowlist_only: bool,
+
+    /// Mapping of allowlisted accounts to the amount of tokens they can
+    /// still mint while `allowlist_only` is set
+    pub allowlist: Mapping<AccountId, u32>,
+
+    /// Price to pay for `mint_with_payment`
+    pub mint_price: Balance,
+
+    /// Payments of `mint_with_payment` not withdrawn yet
+    pub mint_proceeds: Balance,
+
+    /// Royalties paid to the collection and not withdrawn yet
+    pub royalty_escrow: Balance,
+
+    /// Account receiving the creator cut of `transfer_paid` sales
+    pub royalty_recipient: Option<AccountId>,
+
+    /// Creator cut of `transfer_paid` sales, in basis points
+    pub royalty_bps: u16,
+
+    /// Whether the `SoldOut` event was already emitted, so it only
+    /// fires the first time `max_supply` is reached
+    pub sold_out_emitted: bool,
+
+    /// Amount of distinct accounts holding at least one token
+    pub holder_count: u32,
+
+    /// Amount of transfers ever made, mints and burns excluded
+    pub transfer_count: u128,
+
+    /// Whether the final metadata was revealed, which can't be undone
+    pub revealed: bool,
+
+    /// URI of the placeholder metadata shared by all tokens until reveal
+    pub unrevealed_uri: String,
+
+    /// Base URI of the final metadata, suffixed with the token id on reveal
+    pub revealed_uri: String,
+
+    /// Blake2x256 hash of the off-chain collection metadata, can only be set once
+    pub collection_metadata_hash: Option<[u8; 32]>,
+
+    /// Maximum amount of items accepted by batch operations, bounding
+    /// their worst-case gas
+    pub max_batch_size: u32,
+
+    /// Maximum length of attribute keys, unlimited if 0
+    pub max_attribute_key_len: u32,
+
+    /// Maximum length of attribute values (or chunks), unlimited if 0
+    pub max_attribute_value_len: u32,
+
+    /// Whether events are also buffered into `pending_events`
+    pub buffer_events: bool,
+
+    /// Events produced since the last `drain_events`, when buffering
+    pub pending_events: Vec<PSP34Event>,
+
+    /// Id of the latest snapshot taken, 0 if none was
+    pub current_snapshot_id: u32,
+
+    /// Mapping of a snapshot id to the total supply at the time it was taken
+    pub snapshot_supplies: Mapping<u32, Balance>,
+
+    /// Mapping of an account to its balance checkpoints, as
+    /// (snapshot id, balance) pairs sorted by snapshot id.
+    /// A checkpoint holds the balance the account had when the snapshot
+    /// was taken, and is only written the first time that balance changes
+    /// afterwards, so untouched accounts cost no storage per snapshot.
+    pub balance_checkpoints: Mapping<AccountId, Vec<(u32, u32)>>,
+
+    /// Mapping of accounts to the account they delegated their votes to
+    pub delegates: Mapping<AccountId, AccountId>,
+
+    /// Mapping of accounts to the votes delegated to them, see `get_votes`
+    pub delegated_votes: Mapping<AccountId, u128>,
+
+    /// Attributes set on every minted token, unless the mint sets the
+    /// same key itself
+    pub default_attributes: Vec<(Vec<u8>, Vec<u8>)>,
+
     /// Mapping of the attributes of each token
     /// The Vec<u8> in the key represents the identifier of the
     /// attribute while the other one represents its value
     pub attributes: Mapping<(Id, Vec<u8>), Vec<u8>>,
 
+    /// Keys of the attributes the metadata helpers read
+    pub well_known_keys: WellKnownKeys,
+
+    /// Mapping of each token to the keys of the attributes it has set
+    pub attribute_keys: Mapping<Id, Vec<Vec<u8>>>,
+
+    /// Mapping of the chunks of large attributes, e.g. full JSON or SVG
+    /// documents, which would not fit a single storage cell.
+    /// The u32 in the key represents the index of the chunk
+    pub attribute_chunks: Mapping<(Id, Vec<u8>, u32), Vec<u8>>,
+
+    /// Mapping of each token to the keys of the attributes it has chunks of
+    pub attribute_chunk_keys: Mapping<Id, Vec<Vec<u8>>>,
+
+    /// Whether burning a token leaves a tombstone in `all_tokens` instead of
+    /// moving the last token into its slot, so that indexes never shift.
+    /// This trades storage, as `all_tokens` never shrinks, for stable
+    /// pagination over `token_by_index`
+    pub stable_enumeration: bool,
+
     /// Stores the token 'id's for all tokens in the collection
     /// Helps with enumerable trait to get 'id' at indexes: token_by_index
-    pub all_tokens: Vec<u128>,
+    pub all_tokens: Vec<Id>,
+
+    /// Maps the 'id's of tokens to the block they were last minted or
+    /// transferred at, for provenance displays
+    pub last_transfer_block: Mapping<Id, u64>,
+
+    /// Maps the 'id's of tokens to the block they become transferable at
+    pub transfer_unlock_block: Mapping<Id, u64>,
+
+    /// Maps the 'id's of soulbound tokens, which can never be transferred
+    pub soulbound: Mapping<Id, bool>,
+
+    /// Maps the 'id's of tokens whose attributes can no longer change
+    pub token_metadata_frozen: Mapping<Id, bool>,
 
     /// Maps the index of 'id's for all tokens to their index in the collection
     /// Helps with enumerable trait to get 'id' at indexes: token_by_index
@@ -71,32 +343,54 @@ pub struct PSP34Data {
     pub owned_tokens_index: Mapping<Id, u128>,
 }
 
+impl Default for PSP34Data {
+    fn default() -> Self {
+        PSP34Data::new()
+    }
+}
+
 // Internal methods here
 impl PSP34Data {
-    /// Verifies if an account is either the owner of the token or is in the
-    /// list of allowed operators
-    fn owner_or_approved(&self, account: AccountId, token: Id) -> bool {
-        let owner = self.owner_of(token.clone());
-
-        match owner {
-            Some(owner) => {
-                account != AccountId::from([0x0; 32])
-                    && (owner == account
-                        || self.allowance(owner, account, Some(token))
-                        || self.allowance(owner, account, None))
-            }
-            None => false,
-        }
+    /// Verifies if an account is either the `owner` of the token or is in
+    /// the list of allowed operators. Whether `owner` actually owns the
+    /// token is up to the caller to check beforehand.
+    fn owner_or_approved(&self, owner: AccountId, account: AccountId, token: Id) -> bool {
+        account != self.dead_address
+            && (owner == account
+                || self.allowance(owner, account, Some(token.clone()))
+                || self.is_global_operator(account)
+                || self.is_delegated_operator(owner, account, token))
+    }
+
+    /// Verifies if `account` was delegated the approval of an operator
+    /// directly approved by `owner` for the `token`, when transitive
+    /// approvals are enabled. Delegators' own delegations aren't followed,
+    /// bounding the check to one hop.
+    fn is_delegated_operator(&self, owner: AccountId, account: AccountId, token: Id) -> bool {
+        self.transitive_approvals
+            && self
+                .operator_delegators
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .any(|delegator| self.allowance(owner, delegator, Some(token.clone())))
     }
 
     /// Removes a token from the list of existing tokens
-    fn remove_token(&mut self, token: Id) -> Result<(), PSP34Error> {
+    fn remove_token(&mut self, token: Id) -> PSP34Result {
         if !self.exists(token.clone()) {
             return Err(PSP34Error::SafeTransferCheckFailed(
                 "token should exist".into(),
             ));
         }
 
+        // In stable mode the slot is left as a tombstone, which
+        // `token_by_index` detects as it is no longer indexed
+        if self.stable_enumeration {
+            self.all_tokens_index.remove(token);
+            return Ok(());
+        }
+
         let last_token_index = (self.all_tokens.len() - 1) as u128;
         let token_index = self.all_tokens_index.get(token.clone()).unwrap();
 
@@ -105,11 +399,10 @@ impl PSP34Data {
         // minted token is burnt) that we still do the swap here to avoid the
         // gas cost of adding an 'if' statement (like in remove_token_from)
 
-        let last_token_id = Id::U128(self.all_tokens[usize::try_from(last_token_index).unwrap()]);
+        let last_token_id = self.all_tokens[usize::try_from(last_token_index).unwrap()].clone();
 
-        self.all_tokens[usize::try_from(token_index).unwrap()] = u128::from(last_token_id.clone());
-        self.all_tokens_index
-            .insert(last_token_id.clone(), &token_index);
+        self.all_tokens[usize::try_from(token_index).unwrap()] = last_token_id.clone();
+        self.all_tokens_index.insert(last_token_id, &token_index);
 
         // This also deletes the contents at the last position of the array
 
@@ -120,34 +413,48 @@ impl PSP34Data {
     }
 
     /// Adds a token to the list of existing tokens
-    fn add_token(&mut self, token: Id) -> Result<(), PSP34Error> {
+    fn add_token(&mut self, token: Id) -> PSP34Result {
         let length = self.all_tokens.len() as u128;
         self.all_tokens_index.insert(token.clone(), &length);
-        self.all_tokens.push(u128::from(token));
+        self.all_tokens.push(token);
         Ok(())
     }
 
     /// Removes an association of a `token` pertaining to an `account`
-    fn remove_token_from(&mut self, account: AccountId, token: Id) -> Result<(), PSP34Error> {
+    fn remove_token_from(&mut self, account: AccountId, token: Id) -> PSP34Result {
         if !self.exists(token.clone()) {
             return Err(PSP34Error::SafeTransferCheckFailed(
                 "token should exist".into(),
             ));
         }
 
-        let count = self.tokens_per_owner.get(&account).map(|t| t - 1).ok_or(
+        let count = self.tokens_per_owner.get(account).map(|t| t - 1).ok_or(
             PSP34Error::SafeTransferCheckFailed("account should exist".into()),
         )?;
 
+        // Both index entries are read upfront so that an inconsistent
+        // index fails the call cleanly instead of trapping
+        let last_token_index = count as u128;
+        let token_index = self
+            .owned_tokens_index
+            .get(token.clone())
+            .ok_or(PSP34Error::InconsistentIndex)?;
+        let last_token_id = self
+            .owned_tokens
+            .get((account, last_token_index))
+            .ok_or(PSP34Error::InconsistentIndex)?;
+
+        self.update_checkpoint(account);
         self.tokens_per_owner.insert(account, &count);
-        self.tokens_owner.remove(&token.clone());
+        self.move_delegated_vote(account, false);
+        self.tokens_owner.remove(token.clone());
+        self.clear_token_allowances(account, token.clone());
 
-        let last_token_index = self.balance_of(account) as u128;
-        let token_index: u128 = self.owned_tokens_index.get(token.clone()).unwrap();
+        if count == 0 {
+            self.holder_count -= 1;
+        }
 
         if token_index != last_token_index {
-            let last_token_id = self.owned_tokens.get((account, last_token_index)).unwrap();
-
             self.owned_tokens
                 .insert((account, token_index), &last_token_id.clone());
 
@@ -161,35 +468,115 @@ impl PSP34Data {
     }
 
     /// Adds a new association between a `token` pertaining to an `account`
-    fn add_token_to(&mut self, account: AccountId, token: Id) -> Result<(), PSP34Error> {
+    fn add_token_to(&mut self, account: AccountId, token: Id) -> PSP34Result {
         if self.exists(token.clone()) {
             return Err(PSP34Error::SafeTransferCheckFailed(
                 "token should not exist".into(),
             ));
         }
 
-        if account == AccountId::from([0; 32]) {
+        if account == self.dead_address {
             return Err(PSP34Error::SafeTransferCheckFailed(
-                "'to' account is zeroed".into(),
+                "'to' account is the dead address".into(),
             ));
         }
 
+        if self
+            .max_balance_per_owner
+            .is_some_and(|max| self.balance_of(account) >= max)
+        {
+            return Err(PSP34Error::BalanceCapExceeded);
+        }
+
         self.inc_qty_owner_tokens(account);
+        self.move_delegated_vote(account, true);
         self.tokens_owner.insert(token.clone(), &account);
 
         let length = (self.balance_of(account) - 1) as u128;
         self.owned_tokens.insert((account, length), &token.clone());
         self.owned_tokens_index.insert(token.clone(), &length);
 
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+        self.last_transfer_block.insert(token, &block_number);
+
         Ok(())
     }
 
     fn add_allowance_operator(&mut self, owner: AccountId, operator: AccountId, token: Id) {
-        self.allowances.insert((owner, operator, token), &true);
+        self.allowances
+            .insert((owner, operator, token.clone()), &true);
+
+        let mut operators = self.operators_for_token(owner, token.clone());
+        if !operators.contains(&operator) {
+            operators.push(operator);
+            self.token_operators.insert((owner, token), &operators);
+            self.token_approval_counts.insert(
+                (owner, operator),
+                &self.token_approval_count(owner, operator).saturating_add(1),
+            );
+        }
     }
 
     fn remove_allowance_operator(&mut self, owner: AccountId, operator: AccountId, token: Id) {
-        self.allowances.insert((owner, operator, token), &false);
+        self.allowances
+            .insert((owner, operator, token.clone()), &false);
+
+        let mut operators = self.operators_for_token(owner, token.clone());
+        if operators.contains(&operator) {
+            self.dec_token_approval_count(owner, operator);
+        }
+        operators.retain(|op| *op != operator);
+        if operators.is_empty() {
+            self.token_operators.remove((owner, token));
+        } else {
+            self.token_operators.insert((owner, token), &operators);
+        }
+    }
+
+    fn set_allowance_all(&mut self, owner: AccountId, operator: AccountId, approved: bool) {
+        self.allowances_all.insert((owner, operator), &approved);
+
+        let mut operators = self.all_operators.get(owner).unwrap_or_default();
+        if approved && !operators.contains(&operator) {
+            operators.push(operator);
+            self.all_operators.insert(owner, &operators);
+        } else if !approved && operators.contains(&operator) {
+            operators.retain(|op| *op != operator);
+            if operators.is_empty() {
+                self.all_operators.remove(owner);
+            } else {
+                self.all_operators.insert(owner, &operators);
+            }
+        }
+    }
+
+    /// Revokes every per-token approval `owner` gave for `token`, so they
+    /// don't linger once the token moves or is burnt
+    fn clear_token_allowances(&mut self, owner: AccountId, token: Id) {
+        for operator in self.operators_for_token(owner, token.clone()) {
+            self.allowances.remove((owner, operator, token.clone()));
+            self.dec_token_approval_count(owner, operator);
+        }
+        self.token_operators.remove((owner, token));
+    }
+
+    fn dec_token_approval_count(&mut self, owner: AccountId, operator: AccountId) {
+        match self.token_approval_count(owner, operator) {
+            0 | 1 => self.token_approval_counts.remove((owner, operator)),
+            count => {
+                self.token_approval_counts
+                    .insert((owner, operator), &(count - 1));
+            }
+        }
+    }
+
+    /// Verifies if `account` holds an approval from `owner` which doesn't
+    /// cover approving others for the `token`, i.e. it is a global or
+    /// delegated operator, or was approved for other tokens of `owner`
+    fn has_other_approval(&self, owner: AccountId, account: AccountId, token: Id) -> bool {
+        self.is_global_operator(account)
+            || self.is_delegated_operator(owner, account, token)
+            || self.token_approval_count(owner, account) > 0
     }
 
     fn is_allowed_single(&self, owner: AccountId, operator: AccountId, token: Id) -> bool {
@@ -209,10 +596,166 @@ impl PSP34Data {
             .map(|t| t + 1)
             .unwrap_or(1);
 
+        self.update_checkpoint(account);
         self.tokens_per_owner.insert(account, &count);
+
+        if count == 1 {
+            self.holder_count += 1;
+        }
+
         count
     }
 
+    /// Adds or removes one vote from the delegate of `account`, if it
+    /// delegated its votes
+    fn move_delegated_vote(&mut self, account: AccountId, increase: bool) {
+        if let Some(delegate) = self.delegates.get(account) {
+            let votes = self.delegated_votes.get(delegate).unwrap_or(0);
+            let votes = if increase {
+                votes.saturating_add(1)
+            } else {
+                votes.saturating_sub(1)
+            };
+            self.delegated_votes.insert(delegate, &votes);
+        }
+    }
+
+    /// Records the current balance of `account` for the latest snapshot,
+    /// must be called before the balance changes
+    fn update_checkpoint(&mut self, account: AccountId) {
+        if self.current_snapshot_id == 0 {
+            return;
+        }
+
+        let mut checkpoints = self.balance_checkpoints.get(account).unwrap_or_default();
+        let last_snapshot_id = checkpoints.last().map(|(id, _)| *id).unwrap_or(0);
+
+        if last_snapshot_id < self.current_snapshot_id {
+            checkpoints.push((self.current_snapshot_id, self.balance_of(account)));
+            self.balance_checkpoints.insert(account, &checkpoints);
+        }
+    }
+
+    fn ensure_mint_active(&self) -> PSP34Result {
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+
+        if self.mint_start.is_some_and(|start| block_number < start)
+            || self.mint_end.is_some_and(|end| block_number > end)
+        {
+            return Err(PSP34Error::MintNotActive);
+        }
+        Ok(())
+    }
+
+    /// Checks that `caller` could mint right now, without consuming its
+    /// allowlist allowance nor starting its cooldown
+    fn ensure_can_mint(&self, caller: AccountId) -> PSP34Result {
+        self.ensure_mint_active()?;
+        self.ensure_allowlisted(caller)?;
+        self.ensure_mint_cooldown_elapsed(caller)
+    }
+
+    fn ensure_mint_cooldown_elapsed(&self, account: AccountId) -> PSP34Result {
+        if self.mint_cooldown_blocks == 0 {
+            return Ok(());
+        }
+
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+
+        if self
+            .last_mint_block
+            .get(account)
+            .is_some_and(|last| block_number.saturating_sub(last) < self.mint_cooldown_blocks)
+        {
+            return Err(PSP34Error::MintCooldown);
+        }
+        Ok(())
+    }
+
+    /// Records a mint of `account` at the current block, if a mint
+    /// cooldown is set
+    fn consume_mint_cooldown(&mut self, account: AccountId) -> PSP34Result {
+        self.ensure_mint_cooldown_elapsed(account)?;
+
+        if self.mint_cooldown_blocks != 0 {
+            let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+            self.last_mint_block.insert(account, &block_number);
+        }
+        Ok(())
+    }
+
+    fn ensure_allowlisted(&self, account: AccountId) -> PSP34Result {
+        if self.allowlist_only && self.allowlist.get(account).unwrap_or(0) == 0 {
+            return Err(PSP34Error::NotAllowlisted);
+        }
+        Ok(())
+    }
+
+    /// Uses one of the allowlist allowance of `account`, if minting is
+    /// restricted to the allowlist
+    fn consume_allowlist(&mut self, account: AccountId) -> PSP34Result {
+        self.ensure_allowlisted(account)?;
+
+        if self.allowlist_only {
+            let remaining = self.allowlist.get(account).unwrap_or(0);
+            self.allowlist.insert(account, &(remaining - 1));
+        }
+        Ok(())
+    }
+
+    fn ensure_batch_size(&self, len: usize) -> PSP34Result {
+        if len > self.max_batch_size as usize {
+            return Err(PSP34Error::BatchTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Adds `key` to the attribute keys of `token`, if missing
+    fn index_attribute_key(&mut self, token: Id, key: &[u8]) {
+        let mut keys = self.attribute_keys.get(token.clone()).unwrap_or_default();
+        if !keys.iter().any(|k| k == key) {
+            keys.push(key.to_vec());
+            self.attribute_keys.insert(token, &keys);
+        }
+    }
+
+    /// Removes `key` from the attribute keys of `token`
+    fn unindex_attribute_key(&mut self, token: Id, key: &[u8]) {
+        let mut keys = self.attribute_keys.get(token.clone()).unwrap_or_default();
+        keys.retain(|k| k != key);
+        if keys.is_empty() {
+            self.attribute_keys.remove(token);
+        } else {
+            self.attribute_keys.insert(token, &keys);
+        }
+    }
+
+    fn ensure_metadata_not_frozen(&self, id: Id) -> PSP34Result {
+        if self.is_token_metadata_frozen(id) {
+            return Err(PSP34Error::MetadataFrozen);
+        }
+        Ok(())
+    }
+
+    fn ensure_attribute_len(&self, key: &[u8], value: &[u8]) -> PSP34Result {
+        let exceeds = |len: usize, max: u32| max != 0 && len > max as usize;
+
+        if exceeds(key.len(), self.max_attribute_key_len)
+            || exceeds(value.len(), self.max_attribute_value_len)
+        {
+            return Err(PSP34Error::AttributeTooLong);
+        }
+        Ok(())
+    }
+
+    /// Buffers a copy of `events` if buffering is enabled, returning them
+    fn record(&mut self, events: Vec<PSP34Event>) -> Vec<PSP34Event> {
+        if self.buffer_events {
+            self.pending_events.extend(events.iter().cloned());
+        }
+        events
+    }
+
     fn exists(&self, id: Id) -> bool {
         self.tokens_owner.contains(&id)
     }
@@ -221,67 +764,797 @@ impl PSP34Data {
 // External methods here
 impl PSP34Data {
     pub fn new() -> PSP34Data {
-        let data = PSP34Data {
+        PSP34Data {
+            storage_version: STORAGE_VERSION,
+            collection_id: None,
             tokens_owner: Default::default(),
             tokens_per_owner: Default::default(),
             allowances: Default::default(),
+            delegates: Default::default(),
+            delegated_votes: Default::default(),
+            default_attributes: Vec::new(),
             attributes: Default::default(),
+            attribute_keys: Default::default(),
+            well_known_keys: Default::default(),
+            attribute_chunks: Default::default(),
+            attribute_chunk_keys: Default::default(),
+            dead_address: AccountId::from([0; 32]),
             total_supply: 0,
+            lifetime_minted: 0,
+            next_id: 0,
+            max_supply: None,
+            reserved_supply: 0,
+            min_balance_to_keep: 0,
+            transfer_burn_bps: 0,
+            max_balance_per_owner: None,
+            mint_start: None,
+            mint_end: None,
+            mint_cooldown_blocks: 0,
+            last_mint_block: Default::default(),
+            external_mints: Default::default(),
+            external_mint_ids: Default::default(),
+            used_burn_nonces: Default::default(),
+            used_permit_nonces: Default::default(),
+            used_vouchers: Default::default(),
+            allowlist_only: false,
+            allowlist: Default::default(),
+            mint_price: 0,
+            mint_proceeds: 0,
+            royalty_escrow: 0,
+            royalty_recipient: None,
+            royalty_bps: 0,
+            sold_out_emitted: false,
+            holder_count: 0,
+            transfer_count: 0,
+            revealed: false,
+            unrevealed_uri: String::new(),
+            revealed_uri: String::new(),
+            collection_metadata_hash: None,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_attribute_key_len: DEFAULT_MAX_ATTRIBUTE_KEY_LEN,
+            max_attribute_value_len: DEFAULT_MAX_ATTRIBUTE_VALUE_LEN,
+            buffer_events: false,
+            pending_events: vec![],
+            current_snapshot_id: 0,
+            snapshot_supplies: Default::default(),
+            balance_checkpoints: Default::default(),
+            stable_enumeration: false,
             all_tokens: vec![],
+            last_transfer_block: Default::default(),
+            transfer_unlock_block: Default::default(),
+            soulbound: Default::default(),
+            token_metadata_frozen: Default::default(),
             all_tokens_index: Default::default(),
             owned_tokens: Default::default(),
             owned_tokens_index: Default::default(),
             allowances_all: Default::default(),
-        };
+            token_operators: Default::default(),
+            all_operators: Default::default(),
+            token_approval_counts: Default::default(),
+            global_operators: Default::default(),
+            transfers_frozen: false,
+            transitive_approvals: false,
+            operator_delegators: Default::default(),
+        }
+    }
+
+    /// Same as `new`, applying the settings given in `config`. The `owner`
+    /// is left to the contract, as `PSP34Data` has no notion of one.
+    pub fn new_with_config(config: PSP34Config) -> PSP34Data {
+        let defaults = PSP34Data::new();
+        PSP34Data {
+            collection_id: config.collection_id,
+            max_supply: config.max_supply,
+            mint_price: config.mint_price.unwrap_or(defaults.mint_price),
+            max_batch_size: config.max_batch_size.unwrap_or(defaults.max_batch_size),
+            well_known_keys: config.well_known_keys.unwrap_or_default(),
+            royalty_recipient: config.royalty_recipient,
+            royalty_bps: config.royalty_bps.unwrap_or(defaults.royalty_bps),
+            mint_cooldown_blocks: config
+                .mint_cooldown_blocks
+                .unwrap_or(defaults.mint_cooldown_blocks),
+            ..defaults
+        }
+    }
+
+    /// Same as `new`, using `dead_address` instead of the zeroed account,
+    /// for chains where the latter is a regular, spendable account.
+    pub fn with_dead_address(dead_address: AccountId) -> PSP34Data {
+        PSP34Data {
+            dead_address,
+            ..PSP34Data::new()
+        }
+    }
+
+    /// Same as `new`, keeping the index of every token in `token_by_index`
+    /// stable across burns, see `stable_enumeration`.
+    pub fn with_stable_enumeration() -> PSP34Data {
+        PSP34Data {
+            stable_enumeration: true,
+            ..PSP34Data::new()
+        }
+    }
+
+    /// Returns the id of the collection, by default the contract's
+    /// account id as `Id::Bytes`.
+    pub fn collection_id(&self) -> Id {
+        self.collection_id.clone().unwrap_or_else(|| {
+            let account_id = ink::env::account_id::<DefaultEnvironment>();
+            Id::Bytes(<_ as AsRef<[u8; 32]>>::as_ref(&account_id).to_vec())
+        })
+    }
 
-        data
+    pub fn dead_address(&self) -> AccountId {
+        self.dead_address
+    }
+
+    pub fn max_batch_size(&self) -> u32 {
+        self.max_batch_size
+    }
+
+    /// Sets the maximum amount of items accepted by batch operations.
+    pub fn set_max_batch_size(&mut self, max_batch_size: u32) {
+        self.max_batch_size = max_batch_size;
+    }
+
+    /// Returns the maximum length of attribute keys and values, 0 meaning
+    /// unlimited.
+    pub fn max_attribute_lens(&self) -> (u32, u32) {
+        (self.max_attribute_key_len, self.max_attribute_value_len)
+    }
+
+    /// Sets the maximum length of attribute keys and values, 0 meaning
+    /// unlimited. Attributes already set aren't affected.
+    pub fn set_max_attribute_lens(&mut self, max_key_len: u32, max_value_len: u32) {
+        self.max_attribute_key_len = max_key_len;
+        self.max_attribute_value_len = max_value_len;
+    }
+
+    /// Enables or disables buffering events, so they can be collected
+    /// with `drain_events` after several operations instead of from the
+    /// return value of each one.
+    ///
+    /// The buffer lives in storage: a contract enabling it must drain
+    /// it before every message returns, or it will keep growing.
+    pub fn set_event_buffering(&mut self, enabled: bool) {
+        self.buffer_events = enabled;
+    }
+
+    /// Returns the events buffered since the last call, in the order the
+    /// operations produced them, and empties the buffer.
+    pub fn drain_events(&mut self) -> Vec<PSP34Event> {
+        core::mem::take(&mut self.pending_events)
+    }
+
+    pub fn storage_version(&self) -> u16 {
+        self.storage_version
     }
 
     pub fn total_supply(&self) -> Balance {
         Balance::from(self.total_supply)
     }
 
-    pub fn balance_of(&self, owner: AccountId) -> u32 {
-        self.tokens_per_owner.get(owner).unwrap_or(0u32)
+    /// Returns the amount of tokens ever minted, burnt ones included.
+    pub fn lifetime_minted(&self) -> u128 {
+        self.lifetime_minted
     }
 
-    pub fn owner_of(&self, id: Id) -> Option<AccountId> {
-        self.tokens_owner.get(id)
+    pub fn max_supply(&self) -> Option<Balance> {
+        self.max_supply
     }
 
-    /// Returns `true` if the operator is approved by the owner to
-    /// withdraw `id` token.  If `id` is `None`, returns `true` if
-    /// the operator is approved to withdraw all owner's tokens.
-    pub fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
-        match id {
-            Some(token) => {
-                self.is_allowed_single(owner, operator, token)
-                    || self.is_allowed_all(owner, operator)
+    /// Returns the maximum amount of tokens that can exist, `Balance::MAX`
+    /// if unlimited.
+    pub fn cap(&self) -> Balance {
+        self.max_supply.unwrap_or(Balance::MAX)
+    }
+
+    /// Returns how many tokens can still be minted, `Balance::MAX` if the
+    /// supply is unlimited.
+    pub fn remaining_supply(&self) -> Balance {
+        match self.max_supply {
+            Some(max_supply) => max_supply.saturating_sub(self.total_supply),
+            None => Balance::MAX,
+        }
+    }
+
+    /// Returns `true` if the whole capped supply was minted, always
+    /// `false` if the supply is unlimited.
+    pub fn is_sold_out(&self) -> bool {
+        self.max_supply
+            .is_some_and(|max_supply| self.total_supply >= max_supply)
+    }
+
+    /// Sets the maximum amount of tokens that can exist, `None` meaning
+    /// unlimited.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if `max_supply` is lower than the current
+    /// total supply plus the reserved supply.
+    pub fn set_max_supply(&mut self, max_supply: Option<Balance>) -> PSP34Result {
+        if let Some(max_supply) = max_supply {
+            if max_supply < self.total_supply.saturating_add(self.reserved_supply) {
+                return Err(PSP34Error::Custom(
+                    "max supply lower than total supply".into(),
+                ));
             }
-            None => self.is_allowed_all(owner, operator),
         }
+
+        self.max_supply = max_supply;
+        Ok(())
     }
 
-    /// Approves `operator` to withdraw  the `id` token from the caller's account.
-    /// If `id` is `None` approves or disapproves the operator for all tokens of the caller.
+    /// Returns the amount of tokens left in the reserve.
+    pub fn reserved_supply(&self) -> Balance {
+        self.reserved_supply
+    }
+
+    /// Sets aside `reserved_supply` tokens for `reserve_mint`, e.g. for
+    /// team or treasury allocations, replacing the previous reserve.
     ///
-    /// An `Approval` event is emitted.
+    /// # Errors
+    ///
+    /// Returns `Custom` error if the reserve doesn't fit in the supply left
+    /// under `max_supply`.
+    pub fn set_reserved_supply(&mut self, reserved_supply: Balance) -> PSP34Result {
+        if self
+            .max_supply
+            .is_some_and(|max| self.total_supply.saturating_add(reserved_supply) > max)
+        {
+            return Err(PSP34Error::Custom(
+                "reserved supply exceeds max supply".into(),
+            ));
+        }
+
+        self.reserved_supply = reserved_supply;
+        Ok(())
+    }
+
+    /// Mints `count` tokens to `to` out of the reserve, regardless of the
+    /// mint window, allowlist and cooldown.
+    ///
+    /// A `Transfer` event is emitted per token.
     ///
     /// # Errors
     ///
-    /// Returns `SelfApprove` error if it is self approve.
+    /// Returns `BatchTooLarge` error if `count` is above `max_batch_size`.
     ///
-    /// Returns `NotApproved` error if caller is not owner of `id`.
-    pub fn approve(
-        &mut self,
-        caller: AccountId,
-        operator: AccountId,
-        id: Option<Id>,
-        approve: bool,
-    ) -> Result<Vec<PSP34Event>, PSP34Error> {
-        let mut owner = caller;
+    /// Returns `Custom` error if `count` is above the reserved supply.
+    ///
+    /// Returns any error of `mint_with_id`.
+    pub fn reserve_mint(&mut self, to: AccountId, count: u32) -> PSP34Result<Vec<PSP34Event>> {
+        self.ensure_batch_size(count as usize)?;
 
-        match id {
+        if Balance::from(count) > self.reserved_supply {
+            return Err(PSP34Error::Custom("not enough reserved supply".into()));
+        }
+
+        let mut events = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            self.reserved_supply -= 1;
+            let id = self.take_next_token_id()?;
+            events.extend(self.mint_with_id(to, id, vec![])?);
+        }
+
+        Ok(events)
+    }
+
+    pub fn max_balance_per_owner(&self) -> Option<u32> {
+        self.max_balance_per_owner
+    }
+
+    /// Sets the maximum amount of tokens a single account can hold, `None`
+    /// meaning unlimited. Applies to mints and transfers alike, accounts
+    /// already above the cap keep their tokens.
+    pub fn set_max_balance_per_owner(&mut self, max_balance_per_owner: Option<u32>) {
+        self.max_balance_per_owner = max_balance_per_owner;
+    }
+
+    pub fn balance_of(&self, owner: AccountId) -> u32 {
+        self.tokens_per_owner.get(owner).unwrap_or(0u32)
+    }
+
+    /// Verifies the consistency of the enumeration and ownership
+    /// bookkeeping, meant to be called by tests after sequences of
+    /// operations rather than by contracts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error describing the first inconsistency found.
+    #[cfg(feature = "invariants")]
+    pub fn check_invariants(&self) -> PSP34Result {
+        let mut holders: Vec<(AccountId, u32)> = Vec::new();
+        let mut indexed: Balance = 0;
+
+        for (index, id) in self.all_tokens.iter().cloned().enumerate() {
+            let index = index as u128;
+
+            if self.all_tokens_index.get(id.clone()) != Some(index) {
+                if self.stable_enumeration {
+                    continue;
+                }
+                return Err(PSP34Error::Custom("all_tokens_index mismatch".into()));
+            }
+            indexed += 1;
+
+            let owner = self
+                .owner_of(id.clone())
+                .ok_or(PSP34Error::Custom("token without owner".into()))?;
+
+            let owner_index = self
+                .owned_tokens_index
+                .get(id.clone())
+                .ok_or(PSP34Error::Custom("owned_tokens_index missing".into()))?;
+
+            if owner_index >= self.balance_of(owner) as u128
+                || self.owned_tokens.get((owner, owner_index)) != Some(id)
+            {
+                return Err(PSP34Error::Custom("owned_tokens_index mismatch".into()));
+            }
+
+            match holders.iter_mut().find(|(account, _)| *account == owner) {
+                Some((_, count)) => *count += 1,
+                None => holders.push((owner, 1)),
+            }
+        }
+
+        if indexed != self.total_supply {
+            return Err(PSP34Error::Custom("all_tokens length mismatch".into()));
+        }
+
+        if holders.len() as u32 != self.holder_count {
+            return Err(PSP34Error::Custom("holder_count mismatch".into()));
+        }
+
+        for (owner, count) in holders {
+            if self.balance_of(owner) != count {
+                return Err(PSP34Error::Custom("tokens_per_owner mismatch".into()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies the approvals of a collection using the older layout, which
+    /// stored the operators approved by an owner in a single `Vec` per
+    /// `(owner, id)`, `None` standing for all the owner's tokens.
+    ///
+    /// Mappings can't be iterated, so the `(owner, id)` keys to migrate
+    /// must be given, e.g. collected from past `Approval` events. A
+    /// contract upgraded with `set_code_hash` declares `old` in its storage
+    /// where the previous version had it, and calls this in batches before
+    /// clearing it.
+    ///
+    /// Returns the amount of approvals copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if more than `max_batch_size` keys are given.
+    #[cfg(feature = "migration")]
+    pub fn migrate_allowances(
+        &mut self,
+        old: &Mapping<(AccountId, Option<Id>), Vec<AccountId>>,
+        keys: Vec<(AccountId, Option<Id>)>,
+    ) -> PSP34Result<u32> {
+        self.ensure_batch_size(keys.len())?;
+
+        let mut migrated = 0u32;
+        for (owner, id) in keys {
+            for operator in old.get((owner, id.clone())).unwrap_or_default() {
+                match id {
+                    Some(ref token) => self.add_allowance_operator(owner, operator, token.clone()),
+                    None => self.set_allowance_all(owner, operator, true),
+                }
+                migrated = migrated.saturating_add(1);
+            }
+        }
+
+        Ok(migrated)
+    }
+
+    /// Returns the block the `id` token becomes transferable at, `None`
+    /// if it is freely transferable.
+    pub fn transfer_unlock_of(&self, id: Id) -> Option<u64> {
+        self.transfer_unlock_block.get(id)
+    }
+
+    /// Returns `true` if the `id` token can't be transferred yet.
+    pub fn is_locked(&self, id: Id) -> bool {
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+        self.transfer_unlock_of(id)
+            .is_some_and(|unlock| block_number < unlock)
+    }
+
+    /// Prevents the `id` token from being transferred before the
+    /// `unlock_block` block, 0 making it freely transferable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    pub fn set_transfer_unlock_block(&mut self, id: Id, unlock_block: u64) -> PSP34Result {
+        if !self.exists(id.clone()) {
+            return Err(PSP34Error::TokenNotExists);
+        }
+
+        if unlock_block == 0 {
+            self.transfer_unlock_block.remove(id);
+        } else {
+            self.transfer_unlock_block.insert(id, &unlock_block);
+        }
+        Ok(())
+    }
+
+    /// Returns the amount of transfers ever made, including transfers to
+    /// self and transfers which burnt the token, see `transfer_burn_bps`,
+    /// but excluding mints and plain burns.
+    pub fn transfer_count(&self) -> u128 {
+        self.transfer_count
+    }
+
+    /// Returns the block the `id` token was last transferred at, or
+    /// minted at if it never was. `None` if it doesn't exist.
+    pub fn last_transfer_of(&self, id: Id) -> Option<u64> {
+        self.last_transfer_block.get(id)
+    }
+
+    /// Returns the owner of each of the `ids`, in the same order, `None`
+    /// for the ones that weren't minted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if more than `max_batch_size` ids are given.
+    pub fn owners_of(&self, ids: Vec<Id>) -> PSP34Result<Vec<Option<AccountId>>> {
+        self.ensure_batch_size(ids.len())?;
+        Ok(ids.into_iter().map(|id| self.owner_of(id)).collect())
+    }
+
+    /// Returns the amount of distinct accounts holding at least one token.
+    pub fn holder_count(&self) -> u32 {
+        self.holder_count
+    }
+
+    /// Takes a snapshot of the balances and total supply, returning its id.
+    ///
+    /// Snapshots are meant to be taken by the owner of the collection,
+    /// the contract should restrict who is allowed to call this.
+    pub fn snapshot(&mut self) -> u32 {
+        self.current_snapshot_id += 1;
+        self.snapshot_supplies
+            .insert(self.current_snapshot_id, &self.total_supply);
+        self.current_snapshot_id
+    }
+
+    /// Returns the balance `account` had when `snapshot_id` was taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if `snapshot_id` wasn't taken yet.
+    pub fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> PSP34Result<u32> {
+        self.ensure_snapshot_exists(snapshot_id)?;
+
+        // the first checkpoint written at or after `snapshot_id` holds the
+        // balance as of `snapshot_id`; if there's none, the balance hasn't
+        // changed since
+        let balance = self
+            .balance_checkpoints
+            .get(account)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|(id, _)| *id >= snapshot_id)
+            .map(|(_, balance)| balance)
+            .unwrap_or_else(|| self.balance_of(account));
+
+        Ok(balance)
+    }
+
+    /// Returns the total supply when `snapshot_id` was taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if `snapshot_id` wasn't taken yet.
+    pub fn total_supply_at(&self, snapshot_id: u32) -> PSP34Result<Balance> {
+        self.ensure_snapshot_exists(snapshot_id)?;
+        Ok(self.snapshot_supplies.get(snapshot_id).unwrap_or(0))
+    }
+
+    fn ensure_snapshot_exists(&self, snapshot_id: u32) -> PSP34Result {
+        if snapshot_id == 0 || snapshot_id > self.current_snapshot_id {
+            return Err(PSP34Error::Custom("snapshot doesn't exist".into()));
+        }
+        Ok(())
+    }
+
+    pub fn owner_of(&self, id: Id) -> Option<AccountId> {
+        self.tokens_owner.get(id)
+    }
+
+    /// Returns the voting power of `account`: its own balance unless it
+    /// delegated it, plus the balances delegated to it.
+    pub fn get_votes(&self, account: AccountId) -> u128 {
+        let own = match self.delegates.get(account) {
+            Some(_) => 0,
+            None => self.balance_of(account) as u128,
+        };
+        own + self.delegated_votes.get(account).unwrap_or(0)
+    }
+
+    /// Returns the account `account` delegated its votes to, if any.
+    pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+        self.delegates.get(account)
+    }
+
+    /// Delegates the votes of `caller`, now and as its balance changes, to
+    /// `to`. Delegating to oneself keeps the votes while opting in to the
+    /// same bookkeeping as other delegates.
+    ///
+    /// A `DelegateChanged` event is emitted, followed by a
+    /// `DelegateVotesChanged` event per delegate whose votes changed.
+    /// Later transfers move votes between delegates without emitting
+    /// `DelegateVotesChanged`, their `Transfer` events being enough to
+    /// follow them.
+    pub fn delegate(&mut self, caller: AccountId, to: AccountId) -> Vec<PSP34Event> {
+        let from_delegate = self.delegates.get(caller);
+        let balance = self.balance_of(caller) as u128;
+
+        let mut events = vec![PSP34Event::DelegateChanged {
+            delegator: caller,
+            from_delegate,
+            to_delegate: to,
+        }];
+
+        let previous_from = self.get_votes(from_delegate.unwrap_or(caller));
+        let previous_to = self.get_votes(to);
+
+        if let Some(from_delegate) = from_delegate {
+            let votes = self.delegated_votes.get(from_delegate).unwrap_or(0);
+            self.delegated_votes
+                .insert(from_delegate, &votes.saturating_sub(balance));
+        }
+        let votes = self.delegated_votes.get(to).unwrap_or(0);
+        self.delegated_votes
+            .insert(to, &votes.saturating_add(balance));
+        self.delegates.insert(caller, &to);
+
+        for (delegate, previous_votes) in [
+            (from_delegate.unwrap_or(caller), previous_from),
+            (to, previous_to),
+        ] {
+            let new_votes = self.get_votes(delegate);
+            if new_votes != previous_votes {
+                events.push(PSP34Event::DelegateVotesChanged {
+                    delegate,
+                    previous_votes,
+                    new_votes,
+                });
+            }
+        }
+
+        self.record(events)
+    }
+
+    /// Returns the owner of the `id` token, the dead address if it wasn't
+    /// minted.
+    pub fn owner_of_or_zero(&self, id: Id) -> AccountId {
+        self.owner_of(id).unwrap_or(self.dead_address)
+    }
+
+    /// Returns the amount of tokens `owner` approved `operator` for one by
+    /// one, not counting an approval for all its tokens.
+    pub fn token_approval_count(&self, owner: AccountId, operator: AccountId) -> u32 {
+        self.token_approval_counts
+            .get((owner, operator))
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if the operator is approved by the owner to
+    /// withdraw `id` token.  If `id` is `None`, returns `true` if
+    /// the operator is approved to withdraw all owner's tokens.
+    pub fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
+        match id {
+            Some(token) => {
+                self.is_allowed_single(owner, operator, token)
+                    || self.is_allowed_all(owner, operator)
+            }
+            None => self.is_allowed_all(owner, operator),
+        }
+    }
+
+    /// Returns the `allowance` of `operator` for each of the `ids` of
+    /// `owner`, positionally aligned with them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if too many ids are given.
+    pub fn allowance_batch(
+        &self,
+        owner: AccountId,
+        operator: AccountId,
+        ids: Vec<Id>,
+    ) -> PSP34Result<Vec<bool>> {
+        self.ensure_batch_size(ids.len())?;
+        let approved_all = self.is_allowed_all(owner, operator);
+        Ok(ids
+            .into_iter()
+            .map(|id| approved_all || self.is_allowed_single(owner, operator, id))
+            .collect())
+    }
+
+    /// Returns `true` if the operator is approved by the owner to
+    /// withdraw all of the owner's tokens.
+    pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+        self.allowance(owner, operator, None)
+    }
+
+    /// Returns the operators `owner` approved for all its tokens.
+    ///
+    /// Such approvals outlive the tokens of `owner`, see
+    /// `has_active_approvals`.
+    pub fn operators_for_all(&self, owner: AccountId) -> Vec<AccountId> {
+        self.all_operators.get(owner).unwrap_or_default()
+    }
+
+    /// Returns `true` if `owner` approved an operator for all its tokens
+    /// and holds any. Such approvals are kept once `owner` holds no token
+    /// anymore, as they apply again to the tokens it receives later, yet
+    /// they aren't active until then.
+    pub fn has_active_approvals(&self, owner: AccountId) -> bool {
+        self.balance_of(owner) > 0 && self.all_operators.contains(owner)
+    }
+
+    /// Returns the approval of `operator` for all the tokens of `owner`.
+    ///
+    /// Approvals don't expire for now, so `expiry` is always `None`.
+    pub fn approval_status(&self, owner: AccountId, operator: AccountId) -> ApprovalStatus {
+        ApprovalStatus {
+            all: self.is_allowed_all(owner, operator),
+            expiry: None,
+        }
+    }
+
+    /// Returns the operator approved for the `id` token, if exactly one
+    /// per-token approval exists for its current owner.
+    pub fn get_approved(&self, id: Id) -> Option<AccountId> {
+        let owner = self.owner_of(id.clone())?;
+
+        match self.operators_for_token(owner, id).as_slice() {
+            [operator] => Some(*operator),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `operator` may move any token of any owner.
+    pub fn is_global_operator(&self, operator: AccountId) -> bool {
+        self.global_operators.get(operator).unwrap_or(false)
+    }
+
+    /// Registers or unregisters `operator` as a global operator, allowed to
+    /// move any token of any owner, including tokens minted afterwards.
+    ///
+    /// Owners never opt in to this, they implicitly trust whoever sets it:
+    /// the contract must restrict this to the collection admin and should
+    /// only register audited contracts (e.g. a staking contract).
+    pub fn set_global_operator(&mut self, operator: AccountId, approved: bool) {
+        if approved {
+            self.global_operators.insert(operator, &true);
+        } else {
+            self.global_operators.remove(operator);
+        }
+    }
+
+    pub fn transfers_frozen(&self) -> bool {
+        self.transfers_frozen
+    }
+
+    /// Freezes or unfreezes every transfer of the collection, e.g. while
+    /// taking a snapshot, without blocking mints and burns.
+    pub fn set_transfers_frozen(&mut self, frozen: bool) {
+        self.transfers_frozen = frozen;
+    }
+
+    pub fn transitive_approvals(&self) -> bool {
+        self.transitive_approvals
+    }
+
+    /// Enables or disables operators delegating their approvals, see
+    /// `delegate_operator`. Owners implicitly trust their operators'
+    /// delegates once enabled, so it is opt-in per collection.
+    pub fn set_transitive_approvals(&mut self, enabled: bool) {
+        self.transitive_approvals = enabled;
+    }
+
+    /// Returns the operators that delegated their approvals to `operator`.
+    pub fn operator_delegators(&self, operator: AccountId) -> Vec<AccountId> {
+        self.operator_delegators.get(operator).unwrap_or_default()
+    }
+
+    /// Delegates (or stops delegating) the approvals `caller` received as
+    /// an operator to `operator`, e.g. a staking contract re-staking the
+    /// tokens it may move. The delegation only applies while transitive
+    /// approvals are enabled, and only to approvals `caller` got directly
+    /// from the owners: delegation doesn't chain further than one hop.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SelfApprove` error if `caller` delegates to itself.
+    ///
+    /// Returns `BatchTooLarge` error if `operator` already has
+    /// `max_batch_size` delegators.
+    pub fn delegate_operator(
+        &mut self,
+        caller: AccountId,
+        operator: AccountId,
+        approved: bool,
+    ) -> PSP34Result {
+        if caller == operator {
+            return Err(PSP34Error::SelfApprove);
+        }
+
+        let mut delegators = self.operator_delegators(operator);
+        delegators.retain(|delegator| *delegator != caller);
+        if approved {
+            delegators.push(caller);
+            self.ensure_batch_size(delegators.len())?;
+        }
+
+        if delegators.is_empty() {
+            self.operator_delegators.remove(operator);
+        } else {
+            self.operator_delegators.insert(operator, &delegators);
+        }
+        Ok(())
+    }
+
+    /// Returns the operators `owner` approved for the `id` token, not
+    /// including the ones approved for all of `owner`'s tokens.
+    pub fn operators_for_token(&self, owner: AccountId, id: Id) -> Vec<AccountId> {
+        self.token_operators.get((owner, id)).unwrap_or_default()
+    }
+
+    /// Returns the owner, approved operators and attribute keys of the
+    /// `id` token in a single read, `None` if it doesn't exist.
+    pub fn token_info(&self, id: Id) -> Option<TokenInfo> {
+        let owner = self.owner_of(id.clone())?;
+        let mut approved_operators = self.operators_for_token(owner, id.clone());
+        let operator_count = approved_operators.len() as u32;
+        approved_operators.truncate(self.max_batch_size as usize);
+
+        Some(TokenInfo {
+            owner,
+            exists: true,
+            approved_operators,
+            operator_count,
+            attribute_keys: self.attribute_keys(id.clone()),
+            attribute_count: self.attribute_count(id),
+        })
+    }
+
+    /// Approves `operator` to withdraw  the `id` token from the caller's account.
+    /// If `id` is `None` approves or disapproves the operator for all tokens of the caller.
+    ///
+    /// An `Approval` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SelfApprove` error if it is self approve.
+    ///
+    /// Returns `NotOwner` error if caller has no relationship to `id`: it
+    /// is neither its owner nor holds any approval from them.
+    ///
+    /// Returns `NotApproved` error if caller holds an approval which
+    /// doesn't cover `id`, e.g. for another token of the owner, or is a
+    /// global or delegated operator: those may move the token, yet not
+    /// approve others to.
+    pub fn approve(
+        &mut self,
+        caller: AccountId,
+        operator: AccountId,
+        id: Option<Id>,
+        approve: bool,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let mut owner = caller;
+
+        match id {
             Some(ref token) => {
                 if self.is_allowed_all(owner, operator) {
                     return Err(PSP34Error::NotAllowedToApprove);
@@ -296,7 +1569,10 @@ impl PSP34Data {
                 }
 
                 if owner != caller && !self.allowance(owner, caller, Some(token.clone())) {
-                    return Err(PSP34Error::NotApproved);
+                    if self.has_other_approval(owner, caller, token.clone()) {
+                        return Err(PSP34Error::NotApproved);
+                    }
+                    return Err(PSP34Error::NotOwner);
                 }
 
                 if approve {
@@ -306,20 +1582,20 @@ impl PSP34Data {
                 }
             }
             None => {
-                if approve {
-                    self.allowances_all.insert((owner, operator), &true);
-                } else {
-                    self.allowances_all.insert((owner, operator), &false);
+                if approve && owner == operator {
+                    return Err(PSP34Error::SelfApprove);
                 }
+
+                self.set_allowance_all(owner, operator, approve);
             }
         }
 
-        Ok(vec![PSP34Event::Approval {
+        Ok(self.record(vec![PSP34Event::Approval {
             owner,
             operator,
             id,
             approved: approve,
-        }])
+        }]))
     }
 
     /// Transfer approved or owned token from caller.
@@ -330,117 +1606,4106 @@ impl PSP34Data {
     ///
     /// Returns `TokenNotExists` error if `id` does not exist.
     ///
-    /// Returns `NotApproved` error if `from` doesn't have allowance for transferring.
+    /// Returns `NotApproved` error if `caller` doesn't have allowance for transferring.
     ///
     /// Returns `SafeTransferCheckFailed` error if `to` doesn't accept transfer.
+    ///
+    /// Returns `BalanceCapExceeded` error if `to` already holds the maximum
+    /// amount of tokens per owner.
     pub fn transfer(
         &mut self,
-        from: AccountId,
+        caller: AccountId,
         to: AccountId,
         id: Id,
         _data: Vec<u8>,
-    ) -> Result<Vec<PSP34Event>, PSP34Error> {
-        Ok(self.transfer_from(from, to, id.clone(), _data)?)
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let owner = self
+            .owner_of(id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        self.transfer_from(caller, owner, to, id, _data)
     }
 
-    pub fn transfer_from(
+    /// Same as `transfer`, setting the `key` attribute of the `id` token
+    /// to `value` once it is transferred, e.g. to stamp provenance on a
+    /// sale. Being allowed to transfer the token allows writing it.
+    ///
+    /// A `Transfer` event is emitted, followed by an `AttributeSet` event.
+    /// The attribute is only written if the transfer succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransferMayBurn` error if `transfer_burn_bps` is set, as the
+    /// token may not survive the transfer to hold the attribute. Rejecting
+    /// only the transfers which would burn would hand every caller a free
+    /// retry, not only the ones simulating the draw beforehand.
+    ///
+    /// Returns any error of `transfer`.
+    pub fn transfer_with_attribute(
         &mut self,
-        from: AccountId,
+        caller: AccountId,
         to: AccountId,
         id: Id,
-        _data: Vec<u8>,
-    ) -> Result<Vec<PSP34Event>, PSP34Error> {
-        if !self.exists(id.clone()) {
-            return Err(PSP34Error::TokenNotExists);
+        data: Vec<u8>,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if self.transfer_burn_bps != 0 {
+            return Err(PSP34Error::TransferMayBurn);
         }
 
-        // check that the `to` account accepts transfers
-        if to == AccountId::from([0; 32]) {
-            return Err(PSP34Error::SafeTransferCheckFailed(
-                "'to' account is zeroed".into(),
-            ));
-        }
+        let mut events = self.transfer(caller, to, id.clone(), data)?;
+        events.extend(self.set_attribute(id, key, value)?);
+        Ok(events)
+    }
 
-        // check that the account performing the transfer has the
-        // perms to do so
-        if !self.owner_or_approved(from, id.clone()) {
-            return Err(PSP34Error::NotApproved);
-        }
+    pub fn min_balance_to_keep(&self) -> u32 {
+        self.min_balance_to_keep
+    }
 
-        self.remove_token_from(from, id.clone())?;
-        self.add_token_to(to, id.clone())?;
+    /// Sets the amount of tokens owners must keep when transferring, 0
+    /// disabling it. Burns and transfers to oneself aren't restricted.
+    pub fn set_min_balance_to_keep(&mut self, min_balance_to_keep: u32) {
+        self.min_balance_to_keep = min_balance_to_keep;
+    }
 
-        Ok(vec![PSP34Event::Transfer {
-            from: Some(from),
-            to: Some(to),
-            id,
-        }])
+    pub fn transfer_burn_bps(&self) -> u16 {
+        self.transfer_burn_bps
     }
 
-    pub fn owners_token_by_index(&self, owner: AccountId, index: u128) -> Option<Id> {
+    /// Sets the chance for transfers to burn the token instead of
+    /// delivering it, in basis points, 0 disabling it.
+    ///
+    /// The draw is auditable but not secret, see `transfer_burns`: holders
+    /// can avoid the burn by only transferring in blocks where the token
+    /// survives. It thins out careless transfers rather than enforcing a
+    /// deflation rate. Paid and attribute-writing transfers are rejected
+    /// while it is set, as they would otherwise charge for or write to a
+    /// token which may be gone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if `transfer_burn_bps` is above 10000.
+    pub fn set_transfer_burn_bps(&mut self, transfer_burn_bps: u16) -> PSP34Result {
+        if transfer_burn_bps > 10_000 {
+            return Err(PSP34Error::Custom("burn chance above 100%".into()));
+        }
+
+        self.transfer_burn_bps = transfer_burn_bps;
+        Ok(())
+    }
+
+    /// Returns whether the transfer of `id` numbered `transfer_count`
+    /// (see `transfer_count`) at `block_number` burns it, given a burn
+    /// chance of `transfer_burn_bps` basis points.
+    ///
+    /// The outcome is drawn from the Blake2x256 hash of the SCALE encoded
+    /// `(block_number, id, transfer_count)`, so anyone can recompute it,
+    /// before the transfer as well as after: a caller can simulate the
+    /// draw and pick the block to transfer in. There is no seed a caller
+    /// couldn't predict on-chain, so the burn can be avoided by design.
+    pub fn transfer_burns(
+        block_number: u64,
+        id: &Id,
+        transfer_count: u128,
+        transfer_burn_bps: u16,
+    ) -> bool {
+        if transfer_burn_bps == 0 {
+            return false;
+        }
+
+        let mut seed = [0u8; 32];
+        hash_bytes::<Blake2x256>(&(block_number, id, transfer_count).encode(), &mut seed);
+
+        let roll = u16::from_le_bytes([seed[0], seed[1]]) % 10_000;
+        roll < transfer_burn_bps
+    }
+
+    /// Transfer the `id` token owned by `from`, on behalf of `caller`.
+    ///
+    /// As in ERC-721, `from` must be the current owner of the token,
+    /// while `caller` must be either the owner or approved by them.
+    ///
+    /// On success a `Transfer` event is emitted. If a transfer burn chance
+    /// is set and `transfer_burns` draws a burn, the token is burnt
+    /// instead, with a `Transfer` event to `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransfersFrozen` error if transfers are frozen.
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `IncorrectOwner` error if `from` isn't the owner of `id`.
+    ///
+    /// Returns `Soulbound` error if `id` is soulbound.
+    ///
+    /// Returns `TokenLocked` error if `id` can't be transferred yet.
+    ///
+    /// Returns `NotApproved` error if `caller` doesn't have allowance for transferring.
+    ///
+    /// Returns `BelowMinBalance` error if `from` would hold less than
+    /// `min_balance_to_keep` tokens afterwards.
+    ///
+    /// Returns `SafeTransferCheckFailed` error if `to` doesn't accept transfer.
+    ///
+    /// Returns `BalanceCapExceeded` error if `to` already holds the maximum
+    /// amount of tokens per owner.
+    pub fn transfer_from(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+        _data: Vec<u8>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.move_token(Some(caller), from, to, id)
+    }
+
+    /// Moves the `id` token from `from` to `to` with the checks of
+    /// `transfer_from`, checking the approval of `caller` unless it is
+    /// `None`.
+    fn move_token(
+        &mut self,
+        caller: Option<AccountId>,
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if self.transfers_frozen {
+            return Err(PSP34Error::TransfersFrozen);
+        }
+
+        let owner = self
+            .owner_of(id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        if owner != from {
+            return Err(PSP34Error::IncorrectOwner);
+        }
+
+        if self.is_soulbound(id.clone()) {
+            return Err(PSP34Error::Soulbound);
+        }
+
+        if self.is_locked(id.clone()) {
+            return Err(PSP34Error::TokenLocked);
+        }
+
+        // check that the `to` account accepts transfers
+        if to == self.dead_address {
+            return Err(PSP34Error::SafeTransferCheckFailed(
+                "'to' account is the dead address".into(),
+            ));
+        }
+
+        // check that the account performing the transfer has the
+        // perms to do so, on behalf of the already verified owner
+        if caller.is_some_and(|caller| !self.owner_or_approved(owner, caller, id.clone())) {
+            return Err(PSP34Error::NotApproved);
+        }
+
+        if from != to && self.balance_of(from) <= self.min_balance_to_keep {
+            return Err(PSP34Error::BelowMinBalance);
+        }
+
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+        if Self::transfer_burns(
+            block_number,
+            &id,
+            self.transfer_count,
+            self.transfer_burn_bps,
+        ) {
+            let events = self.burn(from, id)?;
+            self.transfer_count = self.transfer_count.saturating_add(1);
+            return Ok(events);
+        }
+
+        self.remove_token_from(from, id.clone())?;
+        self.add_token_to(to, id.clone())?;
+        self.transfer_count = self.transfer_count.saturating_add(1);
+
+        Ok(self.record(vec![PSP34Event::Transfer {
+            from: Some(from),
+            to: Some(to),
+            id,
+            operator: caller.filter(|caller| *caller != from),
+        }]))
+    }
+
+    /// Moves the `id` token from `from` to `to` without checking whether
+    /// anyone is allowed to do so, saving the allowance lookups of
+    /// `transfer_from`.
+    ///
+    /// Every other check of `transfer_from` applies, transfer burn
+    /// included, so both leave the same storage state.
+    ///
+    /// # Safety
+    ///
+    /// This bypasses the approval system entirely: the contract must only
+    /// expose it to a trusted role (e.g. the contract owner or a minter),
+    /// never to arbitrary callers.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error of `transfer_from`, but `NotApproved`.
+    pub fn transfer_unchecked(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.move_token(None, from, to, id)
+    }
+
+    /// Moves the `id` token from `from` to `to` on behalf of the collection
+    /// admin, e.g. to reclaim stolen assets, regardless of approvals.
+    ///
+    /// A clawback must succeed whatever the holder did, so unlike
+    /// `transfer_from` it also ignores frozen transfers, the transfer lock
+    /// of `id`, `min_balance_to_keep` and the transfer burn chance.
+    ///
+    /// A `Transfer` event is emitted, followed by an `AdminTransfer` event
+    /// so indexers can tell clawbacks apart from regular transfers.
+    ///
+    /// # Safety
+    ///
+    /// As with `transfer_unchecked`, the contract must only expose it to
+    /// its owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `IncorrectOwner` error if `from` is not the owner of `id`.
+    ///
+    /// Returns `Soulbound` error if `id` is soulbound.
+    ///
+    /// Returns `SafeTransferCheckFailed` error if `to` is the dead address.
+    ///
+    /// Returns `BalanceCapExceeded` error if `to` already holds the maximum
+    /// amount of tokens per owner.
+    pub fn admin_transfer(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let owner = self
+            .owner_of(id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        if owner != from {
+            return Err(PSP34Error::IncorrectOwner);
+        }
+
+        if self.is_soulbound(id.clone()) {
+            return Err(PSP34Error::Soulbound);
+        }
+
+        if to == self.dead_address {
+            return Err(PSP34Error::SafeTransferCheckFailed(
+                "'to' account is the dead address".into(),
+            ));
+        }
+
+        self.remove_token_from(from, id.clone())?;
+        self.add_token_to(to, id.clone())?;
+        self.transfer_count = self.transfer_count.saturating_add(1);
+
+        Ok(self.record(vec![
+            PSP34Event::Transfer {
+                from: Some(from),
+                to: Some(to),
+                id: id.clone(),
+                operator: None,
+            },
+            PSP34Event::AdminTransfer { from, to, id },
+        ]))
+    }
+
+    /// Changes the id of the `old_id` token to `new_id`, e.g. to move a
+    /// collection to content-hash ids, keeping its owner, approvals,
+    /// attributes and enumeration slots rather than burning and re-minting
+    /// it.
+    ///
+    /// Chunked attributes are moved up to their first missing chunk, i.e.
+    /// as far as `get_attribute_full` reads them. A token minted by
+    /// `mint_idempotent` stays the `external_mint` of its external id.
+    ///
+    /// A `Rekeyed` event is emitted.
+    ///
+    /// # Safety
+    ///
+    /// The contract must only expose it to its owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidId` error if `new_id` is an empty `Id::Bytes`.
+    ///
+    /// Returns `TokenNotExists` error if `old_id` does not exist.
+    ///
+    /// Returns `TokenExists` error if `new_id` already exists.
+    ///
+    /// Returns `InconsistentIndex` error if the index of `old_id` in the
+    /// list of all tokens is out of range.
+    pub fn rekey(&mut self, old_id: Id, new_id: Id) -> PSP34Result<Vec<PSP34Event>> {
+        if matches!(new_id, Id::Bytes(ref bytes) if bytes.is_empty()) {
+            return Err(PSP34Error::InvalidId);
+        }
+
+        let owner = self
+            .owner_of(old_id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        if self.exists(new_id.clone()) {
+            return Err(PSP34Error::TokenExists);
+        }
+
+        // Checked upfront so that an inconsistent index fails the call
+        // before anything moved
+        let all_tokens_slot = match self.all_tokens_index.get(old_id.clone()) {
+            Some(index) => {
+                let slot = usize::try_from(index)
+                    .ok()
+                    .filter(|slot| *slot < self.all_tokens.len())
+                    .ok_or(PSP34Error::InconsistentIndex)?;
+                Some((index, slot))
+            }
+            None => None,
+        };
+
+        self.tokens_owner.remove(old_id.clone());
+        self.tokens_owner.insert(new_id.clone(), &owner);
+
+        if let Some((index, slot)) = all_tokens_slot {
+            self.all_tokens_index.remove(old_id.clone());
+            self.all_tokens[slot] = new_id.clone();
+            self.all_tokens_index.insert(new_id.clone(), &index);
+        }
+
+        if let Some(index) = self.owned_tokens_index.take(old_id.clone()) {
+            self.owned_tokens.insert((owner, index), &new_id);
+            self.owned_tokens_index.insert(new_id.clone(), &index);
+        }
+
+        if let Some(operators) = self.token_operators.take((owner, old_id.clone())) {
+            for operator in operators.iter() {
+                self.allowances.remove((owner, *operator, old_id.clone()));
+                self.allowances
+                    .insert((owner, *operator, new_id.clone()), &true);
+            }
+            self.token_operators
+                .insert((owner, new_id.clone()), &operators);
+        }
+
+        if let Some(keys) = self.attribute_keys.take(old_id.clone()) {
+            for key in keys.iter() {
+                if let Some(value) = self.attributes.take((old_id.clone(), key.clone())) {
+                    self.attributes
+                        .insert((new_id.clone(), key.clone()), &value);
+                }
+            }
+            self.attribute_keys.insert(new_id.clone(), &keys);
+        }
+
+        if let Some(keys) = self.attribute_chunk_keys.take(old_id.clone()) {
+            for key in keys.iter() {
+                let mut chunk_index = 0;
+                while let Some(chunk) =
+                    self.attribute_chunks
+                        .take((old_id.clone(), key.clone(), chunk_index))
+                {
+                    self.attribute_chunks
+                        .insert((new_id.clone(), key.clone(), chunk_index), &chunk);
+                    chunk_index += 1;
+                }
+            }
+            self.attribute_chunk_keys.insert(new_id.clone(), &keys);
+        }
+
+        if let Some(block) = self.last_transfer_block.take(old_id.clone()) {
+            self.last_transfer_block.insert(new_id.clone(), &block);
+        }
+        if let Some(block) = self.transfer_unlock_block.take(old_id.clone()) {
+            self.transfer_unlock_block.insert(new_id.clone(), &block);
+        }
+        if self.soulbound.take(old_id.clone()).is_some() {
+            self.soulbound.insert(new_id.clone(), &true);
+        }
+        if self.token_metadata_frozen.take(old_id.clone()).is_some() {
+            self.token_metadata_frozen.insert(new_id.clone(), &true);
+        }
+        // The entry may be stale if `old_id` was burnt and minted again
+        if let Some(external_id) = self.external_mint_ids.take(old_id.clone()) {
+            if self.external_mints.get(external_id.clone()) == Some(old_id.clone()) {
+                self.external_mints.insert(external_id.clone(), &new_id);
+                self.external_mint_ids.insert(new_id.clone(), &external_id);
+            }
+        }
+
+        Ok(self.record(vec![PSP34Event::Rekeyed { old_id, new_id }]))
+    }
+
+    /// Returns the token at `index` of the token list of `owner`.
+    ///
+    /// Returns `None` for an `index` past the balance of `owner`, rather
+    /// than a stale entry left behind in `owned_tokens`.
+    pub fn owners_token_by_index(&self, owner: AccountId, index: u128) -> Option<Id> {
+        if index >= u128::from(self.balance_of(owner)) {
+            return None;
+        }
+
         self.owned_tokens.get((owner, index))
     }
 
+    /// Returns the token at `index` of the list of all tokens.
+    ///
+    /// In stable enumeration mode, returns `None` for the slots of burnt
+    /// tokens, and indexes go up to the amount of tokens ever minted rather
+    /// than `total_supply`.
     pub fn token_by_index(&self, index: u128) -> Option<Id> {
-        if index >= self.all_tokens.len().try_into().unwrap() {
+        let id = self.all_tokens.get(usize::try_from(index).ok()?)?.clone();
+
+        if self.stable_enumeration && self.all_tokens_index.get(id.clone()) != Some(index) {
             return None;
         }
-        Some(Id::U128(
-            self.all_tokens[usize::try_from(index).unwrap()].into(),
-        ))
+
+        Some(id)
     }
 
-    pub fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
-        self.attributes.get((id, key))
+    /// Returns the first existing token at or after `from_index` in the
+    /// list of all tokens, along with its index, skipping the slots of
+    /// burnt tokens in stable enumeration mode. Resuming from the returned
+    /// index plus one pages through the live tokens only.
+    pub fn next_existing_token(&self, from_index: u128) -> Option<(u128, Id)> {
+        let from = usize::try_from(from_index).ok()?;
+        self.all_tokens
+            .iter()
+            .enumerate()
+            .skip(from)
+            .map(|(index, id)| (index as u128, id))
+            .find(|(index, id)| {
+                !self.stable_enumeration || self.all_tokens_index.get(*id) == Some(*index)
+            })
+            .map(|(index, id)| (index, id.clone()))
+    }
+
+    pub fn well_known_keys(&self) -> WellKnownKeys {
+        self.well_known_keys.clone()
+    }
+
+    /// Returns the name of the collection, its `name` well-known attribute.
+    pub fn collection_name(&self) -> Option<Vec<u8>> {
+        self.get_attribute(self.collection_id(), self.well_known_keys.name.clone())
+    }
+
+    /// Returns the symbol of the collection, its `symbol` well-known
+    /// attribute.
+    pub fn collection_symbol(&self) -> Option<Vec<u8>> {
+        self.get_attribute(self.collection_id(), self.well_known_keys.symbol.clone())
+    }
+
+    /// Returns the base URI of the collection, its `base_uri` well-known
+    /// attribute.
+    pub fn base_uri(&self) -> Option<Vec<u8>> {
+        self.get_attribute(self.collection_id(), self.well_known_keys.base_uri.clone())
     }
 
-    pub fn mint(&mut self, account: AccountId) -> Result<Vec<PSP34Event>, PSP34Error> {
-        self.mint_with_attributes(account, vec![])
+    /// Sets the `key` attribute of the collection itself to `value`, e.g.
+    /// its name under `well_known_keys`. Collection attributes are stored
+    /// under `collection_id`.
+    ///
+    /// An `AttributeSet` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AttributeTooLong` error if `key` or `value` is too long.
+    pub fn set_collection_attribute(
+        &mut self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.ensure_attribute_len(&key, &value)?;
+
+        let id = self.collection_id();
+        self.attributes.insert((id.clone(), key.clone()), &value);
+
+        Ok(self.record(vec![PSP34Event::AttributeSet {
+            id,
+            key,
+            data: value,
+        }]))
     }
 
-    pub fn burn(&mut self, account: AccountId, id: Id) -> Result<Vec<PSP34Event>, PSP34Error> {
+    /// Sets the `key` attribute of the `id` token to `value`.
+    ///
+    /// An `AttributeSet` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `MetadataFrozen` error if the attributes of `id` are frozen.
+    ///
+    /// Returns `AttributeTooLong` error if `key` or `value` is too long.
+    pub fn set_attribute(
+        &mut self,
+        id: Id,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
         if !self.exists(id.clone()) {
             return Err(PSP34Error::TokenNotExists);
         }
 
-        self.total_supply -= 1;
-
-        self.remove_token(id.clone())?;
+        self.ensure_metadata_not_frozen(id.clone())?;
+        self.ensure_attribute_len(&key, &value)?;
 
-        self.remove_token_from(account, id.clone())?;
+        self.attributes.insert((id.clone(), key.clone()), &value);
+        self.index_attribute_key(id.clone(), &key);
 
-        Ok(vec![PSP34Event::Transfer {
-            from: Some(account),
-            to: None,
+        Ok(self.record(vec![PSP34Event::AttributeSet {
             id,
-        }])
+            key,
+            data: value,
+        }]))
     }
 
-    // Mint a token of 'id' with attributes set:
-    // attributes: Vec<(Vec<u8>, Vec<u8>)>
-
-    pub fn mint_with_attributes(
+    /// Sets each of the `attributes` of the `id` token on behalf of
+    /// `caller`, replacing the value of keys that were already set.
+    ///
+    /// An `AttributeSet` event is emitted per attribute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `NotApproved` error if `caller` isn't the owner of `id`
+    /// nor approved by them.
+    ///
+    /// Returns `BatchTooLarge` error if more than `max_batch_size`
+    /// attributes are given.
+    pub fn set_attributes(
         &mut self,
-        account: AccountId,
+        caller: AccountId,
+        id: Id,
         attributes: Vec<(Vec<u8>, Vec<u8>)>,
-    ) -> Result<Vec<PSP34Event>, PSP34Error> {
-        let id = Id::U128(self.total_supply());
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.ensure_batch_size(attributes.len())?;
 
-        self.total_supply += 1;
+        let owner = self
+            .owner_of(id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
 
-        self.add_token(id.clone())?;
+        if !self.owner_or_approved(owner, caller, id.clone()) {
+            return Err(PSP34Error::NotApproved);
+        }
 
-        self.add_token_to(account, id.clone())?;
+        let mut events = Vec::with_capacity(attributes.len());
+        for (key, value) in attributes {
+            events.extend(self.set_attribute(id.clone(), key, value)?);
+        }
 
-        for i in 0..attributes.len() {
-            let (key, value) = &attributes[i];
-            self.attributes.insert((id.clone(), key.clone()), value);
+        Ok(events)
+    }
+
+    /// Removes the `key` attribute of the `id` token on behalf of `caller`.
+    ///
+    /// An `AttributeSet` event with empty `data` is emitted, as PSP34
+    /// has no event dedicated to removals.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `NotApproved` error if `caller` isn't the owner of `id`
+    /// nor approved by them.
+    ///
+    /// Returns `MetadataFrozen` error if the attributes of `id` are frozen.
+    pub fn remove_attribute(
+        &mut self,
+        caller: AccountId,
+        id: Id,
+        key: Vec<u8>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let owner = self
+            .owner_of(id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        if !self.owner_or_approved(owner, caller, id.clone()) {
+            return Err(PSP34Error::NotApproved);
         }
 
-        Ok(vec![PSP34Event::Transfer {
-            from: None,
-            to: Some(account),
-            id: id.clone(),
-        }])
+        self.ensure_metadata_not_frozen(id.clone())?;
+
+        self.attributes.remove((id.clone(), key.clone()));
+        self.unindex_attribute_key(id.clone(), &key);
+
+        Ok(self.record(vec![PSP34Event::AttributeSet {
+            id,
+            key,
+            data: Vec::new(),
+        }]))
+    }
+
+    /// Returns `true` if the attributes of the `id` token can no longer
+    /// change.
+    pub fn is_token_metadata_frozen(&self, id: Id) -> bool {
+        self.token_metadata_frozen.get(id).unwrap_or(false)
+    }
+
+    /// Freezes the attributes of the `id` token for good, e.g. once it
+    /// is revealed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    pub fn freeze_token_metadata(&mut self, id: Id) -> PSP34Result {
+        if !self.exists(id.clone()) {
+            return Err(PSP34Error::TokenNotExists);
+        }
+
+        self.token_metadata_frozen.insert(id, &true);
+        Ok(())
+    }
+
+    /// Stores `data` as the chunk `chunk_index` of the `key` attribute of
+    /// the `id` token, overwriting the chunk if it was already set.
+    ///
+    /// Chunks are read back by `get_attribute_full` from index 0 until the
+    /// first missing one, so they must be written without gaps.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `MetadataFrozen` error if the attributes of `id` are frozen.
+    ///
+    /// Returns `AttributeTooLong` error if `key` or `data` is too long.
+    pub fn set_attribute_chunk(
+        &mut self,
+        id: Id,
+        key: Vec<u8>,
+        chunk_index: u32,
+        data: Vec<u8>,
+    ) -> PSP34Result {
+        if !self.exists(id.clone()) {
+            return Err(PSP34Error::TokenNotExists);
+        }
+
+        self.ensure_metadata_not_frozen(id.clone())?;
+        self.ensure_attribute_len(&key, &data)?;
+
+        let mut keys = self
+            .attribute_chunk_keys
+            .get(id.clone())
+            .unwrap_or_default();
+        if !keys.contains(&key) {
+            keys.push(key.clone());
+            self.attribute_chunk_keys.insert(id.clone(), &keys);
+        }
+
+        self.attribute_chunks.insert((id, key, chunk_index), &data);
+        Ok(())
+    }
+
+    /// Returns the `key` attribute of the `id` token reassembled from its
+    /// chunks, `None` if it has no first chunk.
+    pub fn get_attribute_full(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
+        let mut value = self.attribute_chunks.get((id.clone(), key.clone(), 0))?;
+
+        let mut chunk_index = 1;
+        while let Some(chunk) = self
+            .attribute_chunks
+            .get((id.clone(), key.clone(), chunk_index))
+        {
+            value.extend(chunk);
+            chunk_index += 1;
+        }
+
+        Some(value)
+    }
+
+    /// Sets the `key` attribute of the `id` token to the SCALE encoded `value`.
+    pub fn set_attribute_u128(
+        &mut self,
+        id: Id,
+        key: Vec<u8>,
+        value: u128,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.set_attribute(id, key, value.encode())
+    }
+
+    /// Returns the `key` attribute of the `id` token decoded as an `u128`,
+    /// `None` if it isn't set or isn't a SCALE encoded `u128`.
+    pub fn get_attribute_u128(&self, id: Id, key: Vec<u8>) -> Option<u128> {
+        let value = self.get_attribute(id, key)?;
+        u128::decode(&mut value.as_slice()).ok()
+    }
+
+    /// Sets the `key` attribute of the `id` token to the SCALE encoded `value`.
+    pub fn set_attribute_string(
+        &mut self,
+        id: Id,
+        key: Vec<u8>,
+        value: String,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.set_attribute(id, key, value.encode())
+    }
+
+    /// Returns the `key` attribute of the `id` token decoded as a `String`,
+    /// `None` if it isn't set or isn't a SCALE encoded `String`.
+    pub fn get_attribute_string(&self, id: Id, key: Vec<u8>) -> Option<String> {
+        let value = self.get_attribute(id, key)?;
+        String::decode(&mut value.as_slice()).ok()
+    }
+
+    /// Returns the index of the `id` token in the list of all tokens,
+    /// `None` if it wasn't minted.
+    pub fn index_of_token(&self, id: Id) -> Option<u128> {
+        self.all_tokens_index.get(id)
+    }
+
+    /// Returns the index of the `id` token in the token list of `owner`,
+    /// `None` if `owner` doesn't own it.
+    pub fn index_of_owner_token(&self, owner: AccountId, id: Id) -> Option<u128> {
+        if self.owner_of(id.clone())? != owner {
+            return None;
+        }
+        self.owned_tokens_index.get(id)
+    }
+
+    pub fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
+        self.attributes.get((id, key))
+    }
+
+    /// Returns the keys of the attributes set on the `id` token, in the
+    /// order they were first set, at most `max_batch_size` of them. The
+    /// rest can be read with `attributes_from`.
+    pub fn attribute_keys(&self, id: Id) -> Vec<Vec<u8>> {
+        let mut keys = self.attribute_keys.get(id).unwrap_or_default();
+        keys.truncate(self.max_batch_size as usize);
+        keys
+    }
+
+    /// Returns the amount of attributes set on the `id` token.
+    pub fn attribute_count(&self, id: Id) -> u32 {
+        self.attribute_keys.get(id).unwrap_or_default().len() as u32
+    }
+
+    /// Returns the attributes set on the `id` token as key/value pairs, at
+    /// most `max_batch_size` of them, i.e. the first page of
+    /// `attributes_from`.
+    pub fn all_attributes(&self, id: Id) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.attributes_from(id, 0)
+    }
+
+    /// Returns at most `max_batch_size` attributes of the `id` token as
+    /// key/value pairs, starting from the `from_index`-th one in the order
+    /// they were first set. All of them are read by paging until
+    /// `attribute_count` is reached.
+    pub fn attributes_from(&self, id: Id, from_index: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.attribute_keys
+            .get(id.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .skip(from_index as usize)
+            .take(self.max_batch_size as usize)
+            .filter_map(|key| {
+                let value = self.get_attribute(id.clone(), key.clone())?;
+                Some((key, value))
+            })
+            .collect()
+    }
+
+    /// Returns the `key` attribute of each of the `ids`, in the same
+    /// order, `None` for the ones lacking it or that weren't minted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if more than `max_batch_size` ids are given.
+    pub fn get_attribute_for_tokens(
+        &self,
+        ids: Vec<Id>,
+        key: Vec<u8>,
+    ) -> PSP34Result<Vec<Option<Vec<u8>>>> {
+        self.ensure_batch_size(ids.len())?;
+        Ok(ids
+            .into_iter()
+            .map(|id| self.get_attribute(id, key.clone()))
+            .collect())
+    }
+
+    /// Same as `get_attribute`, telling apart a token that doesn't exist
+    /// from an existing one lacking the `key` attribute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    pub fn try_get_attribute(&self, id: Id, key: Vec<u8>) -> PSP34Result<Option<Vec<u8>>> {
+        if !self.exists(id.clone()) {
+            return Err(PSP34Error::TokenNotExists);
+        }
+        Ok(self.get_attribute(id, key))
+    }
+
+    /// Returns the metadata URI of the `id` token, `None` if it doesn't
+    /// exist. A token minted with an explicit URI, see `mint_with_uri`,
+    /// always returns it. Otherwise, before reveal every token shares the
+    /// placeholder URI, after it the URI is the revealed base URI followed
+    /// by the token id.
+    pub fn token_uri(&self, id: Id) -> Option<String> {
+        if !self.exists(id.clone()) {
+            return None;
+        }
+
+        if let Some(uri) = self
+            .get_attribute(id.clone(), self.well_known_keys.uri.clone())
+            .and_then(|uri| String::from_utf8(uri).ok())
+        {
+            return Some(uri);
+        }
+
+        if !self.revealed {
+            return Some(self.unrevealed_uri.clone());
+        }
+
+        let id = match id {
+            Id::U8(val) => format!("{}", val),
+            Id::U16(val) => format!("{}", val),
+            Id::U32(val) => format!("{}", val),
+            Id::U64(val) => format!("{}", val),
+            Id::U128(val) => format!("{}", val),
+            Id::Bytes(val) => val.iter().map(|b| format!("{:02x}", b)).collect(),
+        };
+        Some(format!("{}{}", self.revealed_uri, id))
+    }
+
+    /// Returns a `data:application/json;base64,` URI embedding the
+    /// attributes of the `id` token as a flat JSON object, for fully
+    /// on-chain collections. `None` if the token doesn't exist.
+    ///
+    /// Keys and values are read as UTF-8, invalid sequences being
+    /// replaced, and at most `max_batch_size` attributes are included.
+    #[cfg(feature = "data-uri")]
+    pub fn data_uri(&self, id: Id) -> Option<Vec<u8>> {
+        if !self.exists(id.clone()) {
+            return None;
+        }
+
+        let fields: Vec<String> = self
+            .all_attributes(id)
+            .iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+            .collect();
+        let json = format!("{{{}}}", fields.join(","));
+
+        let mut uri = b"data:application/json;base64,".to_vec();
+        uri.extend(base64_encode(json.as_bytes()));
+        Some(uri)
+    }
+
+    pub fn revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// Sets the placeholder URI and the final base URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if the collection was already revealed.
+    pub fn set_reveal_uris(&mut self, unrevealed_uri: String, revealed_uri: String) -> PSP34Result {
+        if self.revealed {
+            return Err(PSP34Error::Custom("already revealed".into()));
+        }
+
+        self.unrevealed_uri = unrevealed_uri;
+        self.revealed_uri = revealed_uri;
+        Ok(())
+    }
+
+    /// Switches every token from the placeholder to its final URI, for
+    /// good.
+    ///
+    /// A `Revealed` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if the collection was already revealed.
+    pub fn reveal(&mut self) -> PSP34Result<Vec<PSP34Event>> {
+        if self.revealed {
+            return Err(PSP34Error::Custom("already revealed".into()));
+        }
+
+        self.revealed = true;
+
+        Ok(self.record(vec![PSP34Event::Revealed {
+            revealed_uri: self.revealed_uri.clone(),
+        }]))
+    }
+
+    pub fn collection_metadata_hash(&self) -> Option<[u8; 32]> {
+        self.collection_metadata_hash
+    }
+
+    /// Sets the hash of the off-chain collection metadata. The hash is
+    /// immutable, meaning it can only be set once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if the hash was already set.
+    pub fn set_collection_metadata_hash(&mut self, hash: [u8; 32]) -> PSP34Result {
+        if self.collection_metadata_hash.is_some() {
+            return Err(PSP34Error::Custom("metadata hash already set".into()));
+        }
+
+        self.collection_metadata_hash = Some(hash);
+        Ok(())
+    }
+
+    /// Returns `true` if the Blake2x256 hash of `preimage` matches the
+    /// collection metadata hash.
+    pub fn verify_metadata(&self, preimage: Vec<u8>) -> bool {
+        match self.collection_metadata_hash {
+            Some(hash) => {
+                let mut output = [0u8; 32];
+                hash_bytes::<Blake2x256>(&preimage, &mut output);
+                output == hash
+            }
+            None => false,
+        }
+    }
+
+    pub fn mint_window(&self) -> (Option<u64>, Option<u64>) {
+        (self.mint_start, self.mint_end)
+    }
+
+    /// Sets the first and last blocks minting is allowed at, `None`
+    /// meaning open-ended.
+    pub fn set_mint_window(&mut self, mint_start: Option<u64>, mint_end: Option<u64>) {
+        self.mint_start = mint_start;
+        self.mint_end = mint_end;
+    }
+
+    pub fn mint_cooldown_blocks(&self) -> u64 {
+        self.mint_cooldown_blocks
+    }
+
+    /// Sets the minimum amount of blocks between two mints of an account,
+    /// 0 disabling the cooldown.
+    ///
+    /// The cooldown applies to the account calling the mint, so minting to
+    /// several recipients doesn't get around it.
+    pub fn set_mint_cooldown_blocks(&mut self, mint_cooldown_blocks: u64) {
+        self.mint_cooldown_blocks = mint_cooldown_blocks;
+    }
+
+    pub fn allowlist_only(&self) -> bool {
+        self.allowlist_only
+    }
+
+    /// Restricts minting to allowlisted accounts, or opens it to everyone.
+    ///
+    /// The allowlist is checked against the account calling the mint, not
+    /// the one receiving the minted token, so that an allowlisted account
+    /// can't be used up by minting to it.
+    pub fn set_allowlist_only(&mut self, allowlist_only: bool) {
+        self.allowlist_only = allowlist_only;
+    }
+
+    /// Returns how many more tokens `account` can mint while minting is
+    /// restricted to the allowlist, 0 if it isn't allowlisted.
+    pub fn allowlist_remaining(&self, account: AccountId) -> u32 {
+        self.allowlist.get(account).unwrap_or(0)
+    }
+
+    /// Allows `account` to mint `amount` tokens while minting is
+    /// restricted to the allowlist, 0 removing it from the allowlist.
+    pub fn set_allowlist(&mut self, account: AccountId, amount: u32) {
+        if amount == 0 {
+            self.allowlist.remove(account);
+        } else {
+            self.allowlist.insert(account, &amount);
+        }
+    }
+
+    pub fn mint_price(&self) -> Balance {
+        self.mint_price
+    }
+
+    /// Sets the price of `mint_with_payment`.
+    ///
+    /// A `MintPriceChanged` event is emitted.
+    pub fn set_mint_price(&mut self, mint_price: Balance) -> Vec<PSP34Event> {
+        let old = core::mem::replace(&mut self.mint_price, mint_price);
+        self.record(vec![PSP34Event::MintPriceChanged {
+            old,
+            new: mint_price,
+        }])
+    }
+
+    /// Mints a new token to `to` on behalf of `caller` provided
+    /// `transferred_value`, the value transferred along with the call,
+    /// covers the mint price.
+    ///
+    /// The payment stays in the contract and is accounted for in
+    /// `mint_proceeds`, see `take_mint_proceeds`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InsufficientPayment` error if `transferred_value` is lower
+    /// than the mint price.
+    pub fn mint_with_payment(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        transferred_value: Balance,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if transferred_value < self.mint_price {
+            return Err(PSP34Error::InsufficientPayment);
+        }
+
+        let events = self.mint(caller, to)?;
+        self.mint_proceeds = self.mint_proceeds.saturating_add(transferred_value);
+        Ok(events)
+    }
+
+    pub fn mint_proceeds(&self) -> Balance {
+        self.mint_proceeds
+    }
+
+    pub fn royalty_escrow(&self) -> Balance {
+        self.royalty_escrow
+    }
+
+    /// Returns the recipient and amount of the creator cut of a sale at
+    /// `sale_price`, `None` if no royalties are configured.
+    pub fn royalty_info(&self, sale_price: Balance) -> Option<(AccountId, Balance)> {
+        let recipient = self.royalty_recipient?;
+        let amount = sale_price.saturating_mul(self.royalty_bps as Balance) / 10_000;
+        Some((recipient, amount))
+    }
+
+    /// Sets the creator cut of `transfer_paid` sales to `royalty_bps` basis
+    /// points, paid to `recipient`. `None` disables royalties.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if `royalty_bps` is above 10000.
+    pub fn set_royalty(&mut self, recipient: Option<AccountId>, royalty_bps: u16) -> PSP34Result {
+        if royalty_bps > 10_000 {
+            return Err(PSP34Error::Custom("royalty above 100%".into()));
+        }
+
+        self.royalty_recipient = recipient;
+        self.royalty_bps = royalty_bps;
+        Ok(())
+    }
+
+    /// Accounts for `amount` of royalties received by the contract.
+    pub fn add_royalty_escrow(&mut self, amount: Balance) {
+        self.royalty_escrow = self.royalty_escrow.saturating_add(amount);
+    }
+
+    /// Returns how much of the contract's `balance` its owner can withdraw
+    /// without dipping into the royalty escrow.
+    pub fn withdrawable(&self, balance: Balance) -> Balance {
+        balance.saturating_sub(self.royalty_escrow)
+    }
+
+    /// Resets the mint proceeds, returning the amount the contract should
+    /// transfer out of its `balance` without dipping into the royalty
+    /// escrow.
+    pub fn take_mint_proceeds(&mut self, balance: Balance) -> Balance {
+        let available = balance.saturating_sub(self.royalty_escrow);
+        let amount = self.mint_proceeds.min(available);
+        self.mint_proceeds -= amount;
+        amount
+    }
+
+    /// Resets the royalty escrow, returning the amount the contract
+    /// should transfer out of its `balance` without dipping into the mint
+    /// proceeds.
+    pub fn take_royalty_escrow(&mut self, balance: Balance) -> Balance {
+        let available = balance.saturating_sub(self.mint_proceeds);
+        let amount = self.royalty_escrow.min(available);
+        self.royalty_escrow -= amount;
+        amount
+    }
+
+    /// Returns the `Id` the next `mint` will assign, the first `Id::U128`
+    /// from `next_id` on that isn't taken, e.g. by `mint_with_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoFreeId` error if `next_id` and the `max_batch_size` ids
+    /// after it are all taken, which burning one of them or raising
+    /// `max_batch_size` resolves.
+    pub fn next_token_id(&self) -> PSP34Result<Id> {
+        (0..=self.max_batch_size as u128)
+            .map_while(|offset| self.next_id.checked_add(offset))
+            .map(Id::U128)
+            .find(|id| !self.exists(id.clone()))
+            .ok_or(PSP34Error::NoFreeId)
+    }
+
+    /// Returns `next_token_id`, moving `next_id` past it
+    fn take_next_token_id(&mut self) -> PSP34Result<Id> {
+        let id = self.next_token_id()?;
+        if let Id::U128(n) = id {
+            self.next_id = n + 1;
+        }
+        Ok(id)
+    }
+
+    pub fn mint(&mut self, caller: AccountId, account: AccountId) -> PSP34Result<Vec<PSP34Event>> {
+        self.mint_with_attributes(caller, account, vec![])
+    }
+
+    /// Returns `true` if the `id` token can never be transferred.
+    pub fn is_soulbound(&self, id: Id) -> bool {
+        self.soulbound.get(id).unwrap_or(false)
+    }
+
+    /// Mints a new token to `account` which can never be transferred,
+    /// only burned, e.g. to issue and revoke credentials.
+    pub fn mint_soulbound(
+        &mut self,
+        caller: AccountId,
+        account: AccountId,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let id = self.next_token_id()?;
+        let events = self.mint(caller, account)?;
+        self.soulbound.insert(id, &true);
+        Ok(events)
+    }
+
+    /// Burns the `id` token of `account` on behalf of `caller`, who must be
+    /// `account` or one of its operators for the token.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotApproved` error if `caller` isn't allowed to burn the
+    /// token.
+    ///
+    /// Returns any error of `burn`.
+    pub fn burn_from(
+        &mut self,
+        caller: AccountId,
+        account: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if !self.owner_or_approved(account, caller, id.clone()) {
+            return Err(PSP34Error::NotApproved);
+        }
+
+        self.burn(account, id)
+    }
+
+    /// Burns the `id` token of `account`.
+    ///
+    /// A `Transfer` event to `None` is emitted, followed by a `Burn` event.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `IncorrectOwner` error if `account` doesn't own `id`.
+    pub fn burn(&mut self, account: AccountId, id: Id) -> PSP34Result<Vec<PSP34Event>> {
+        match self.owner_of(id.clone()) {
+            None => return Err(PSP34Error::TokenNotExists),
+            Some(owner) if owner != account => return Err(PSP34Error::IncorrectOwner),
+            _ => {}
+        }
+
+        self.total_supply -= 1;
+
+        self.remove_token(id.clone())?;
+
+        self.remove_token_from(account, id.clone())?;
+
+        self.last_transfer_block.remove(id.clone());
+        self.transfer_unlock_block.remove(id.clone());
+        self.soulbound.remove(id.clone());
+        self.token_metadata_frozen.remove(id.clone());
+
+        Ok(self.record(vec![
+            PSP34Event::Transfer {
+                from: Some(account),
+                to: None,
+                id: id.clone(),
+                operator: None,
+            },
+            PSP34Event::Burn { from: account, id },
+        ]))
+    }
+
+    /// Burns the `old_id` token of `caller` and mints a new one to them
+    /// with `new_attributes`, e.g. to re-roll the traits of a token.
+    ///
+    /// Returns the events of both the burn and the mint, followed by an
+    /// `AttributeSet` event per new attribute. The checks of the mint run
+    /// before the burn, so a failed swap leaves `old_id` as it was.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `old_id` does not exist.
+    ///
+    /// Returns `NotApproved` error if `caller` isn't the owner of `old_id`.
+    ///
+    /// Returns any error raised by the mint, e.g. `MintNotActive` or
+    /// `AttributeTooLong`.
+    pub fn swap(
+        &mut self,
+        caller: AccountId,
+        old_id: Id,
+        new_attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let owner = self
+            .owner_of(old_id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        if owner != caller {
+            return Err(PSP34Error::NotApproved);
+        }
+
+        // Whatever the mint could fail on is checked before burning, so a
+        // failed swap leaves `old_id` untouched even outside of a contract
+        // reverting the call
+        self.ensure_can_mint(owner)?;
+        for (key, value) in &new_attributes {
+            self.ensure_attribute_len(key, value)?;
+        }
+
+        let mut events = self.burn(owner, old_id)?;
+        let new_id = self.next_token_id()?;
+        events.extend(self.mint_with_attributes(owner, owner, new_attributes.clone())?);
+
+        let attribute_events = new_attributes
+            .into_iter()
+            .map(|(key, data)| PSP34Event::AttributeSet {
+                id: new_id.clone(),
+                key,
+                data,
+            })
+            .collect();
+        events.extend(self.record(attribute_events));
+
+        Ok(events)
+    }
+
+    /// Mints one token to each of the `recipients`, in order, regardless
+    /// of the mint window, allowlist and cooldown. Recipients listed
+    /// several times get several tokens.
+    ///
+    /// A `Transfer` event is emitted per token.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if there are more recipients than
+    /// `max_batch_size`.
+    ///
+    /// Returns `ReachedMaxSupply` error if the supply left outside of the
+    /// reserve can't cover every recipient, in which case nothing is minted.
+    ///
+    /// Returns any error of `mint_with_id`.
+    pub fn airdrop(&mut self, recipients: Vec<AccountId>) -> PSP34Result<Vec<PSP34Event>> {
+        self.ensure_batch_size(recipients.len())?;
+
+        if self.max_supply.is_some_and(|max| {
+            self.total_supply
+                .saturating_add(self.reserved_supply)
+                .saturating_add(recipients.len() as Balance)
+                > max
+        }) {
+            return Err(PSP34Error::ReachedMaxSupply);
+        }
+
+        let mut events = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            let id = self.take_next_token_id()?;
+            events.extend(self.mint_with_id(recipient, id, vec![])?);
+        }
+
+        Ok(events)
+    }
+
+    /// Picks one option of each of the `trait_pools`, keyed by attribute
+    /// key, from the Blake2x256 hash of `(block_number, id, key)`.
+    ///
+    /// The selection only depends on on-chain data, so the traits of a
+    /// token minted by `mint_random` can be recomputed by anyone. Pools
+    /// without options are skipped.
+    pub fn random_attributes(
+        block_number: u64,
+        id: &Id,
+        trait_pools: &[(Vec<u8>, Vec<Vec<u8>>)],
+    ) -> Vec<(Vec<u8>, Vec<u8>)> {
+        trait_pools
+            .iter()
+            .filter(|(_, options)| !options.is_empty())
+            .map(|(key, options)| {
+                let mut seed = [0u8; 32];
+                hash_bytes::<Blake2x256>(&(block_number, id, key).encode(), &mut seed);
+
+                let mut value = [0u8; 16];
+                value.copy_from_slice(&seed[..16]);
+                let index = u128::from_le_bytes(value) % options.len() as u128;
+
+                (key.clone(), options[index as usize].clone())
+            })
+            .collect()
+    }
+
+    /// Mints a new token to `account` with one option of each of the
+    /// `trait_pools` set as attribute, see `random_attributes`.
+    ///
+    /// The seed is known ahead of the mint, and to some extent chosen by
+    /// block authors: the contract must not let the minter pick the pools
+    /// nor retry a mint until it gets a rare trait.
+    ///
+    /// A `Transfer` event is emitted, followed by an `AttributeSet` event
+    /// per trait.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if too many pools are given.
+    ///
+    /// Returns `Custom` error if a pool has no options.
+    ///
+    /// Returns any error of `mint_with_attributes`.
+    pub fn mint_random(
+        &mut self,
+        caller: AccountId,
+        account: AccountId,
+        trait_pools: Vec<(Vec<u8>, Vec<Vec<u8>>)>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.ensure_batch_size(trait_pools.len())?;
+
+        if trait_pools.iter().any(|(_, options)| options.is_empty()) {
+            return Err(PSP34Error::Custom("empty trait pool".into()));
+        }
+
+        let id = self.next_token_id()?;
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+        let attributes = Self::random_attributes(block_number, &id, &trait_pools);
+
+        let mut events = self.mint_with_attributes(caller, account, attributes.clone())?;
+
+        let attribute_events = attributes
+            .into_iter()
+            .map(|(key, data)| PSP34Event::AttributeSet {
+                id: id.clone(),
+                key,
+                data,
+            })
+            .collect();
+        events.extend(self.record(attribute_events));
+
+        Ok(events)
+    }
+
+    pub fn default_attributes(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.default_attributes.clone()
+    }
+
+    /// Sets the attributes applied to every token minted from now on,
+    /// replacing the previous ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if too many attributes are given.
+    ///
+    /// Returns `AttributeTooLong` error if any of the `attributes` is too
+    /// long.
+    pub fn set_default_attributes(&mut self, attributes: Vec<(Vec<u8>, Vec<u8>)>) -> PSP34Result {
+        self.ensure_batch_size(attributes.len())?;
+        for (key, value) in &attributes {
+            self.ensure_attribute_len(key, value)?;
+        }
+
+        self.default_attributes = attributes;
+        Ok(())
+    }
+
+    // Mint a token of 'id' with attributes set:
+    // attributes: Vec<(Vec<u8>, Vec<u8>)>
+
+    pub fn mint_with_attributes(
+        &mut self,
+        caller: AccountId,
+        account: AccountId,
+        attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.mint_with_hooks(&mut (), caller, account, attributes)
+    }
+
+    /// Mints a new token to `account` with `uri` as its explicit metadata
+    /// URI, stored as the `uri` well-known attribute.
+    ///
+    /// An `AttributeSet` event for the URI is emitted after the mint
+    /// events.
+    pub fn mint_with_uri(
+        &mut self,
+        caller: AccountId,
+        account: AccountId,
+        uri: Vec<u8>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let id = self.next_token_id()?;
+        let key = self.well_known_keys.uri.clone();
+
+        let mut events =
+            self.mint_with_attributes(caller, account, vec![(key.clone(), uri.clone())])?;
+        events.extend(self.record(vec![PSP34Event::AttributeSet { id, key, data: uri }]));
+
+        Ok(events)
+    }
+
+    /// Returns the token minted by `mint_idempotent` for `external_id`.
+    pub fn external_mint(&self, external_id: Vec<u8>) -> Option<Id> {
+        self.external_mints.get(external_id)
+    }
+
+    /// Same as `mint_with_attributes`, minting at most once per
+    /// `external_id`, e.g. an order id, so that a backend can safely retry
+    /// a mint whose outcome it didn't get. A repeated call succeeds
+    /// without minting nor emitting anything, whatever its `account` and
+    /// `attributes`, see `external_mint` for the token minted the first
+    /// time.
+    pub fn mint_idempotent(
+        &mut self,
+        caller: AccountId,
+        account: AccountId,
+        external_id: Vec<u8>,
+        attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if self.external_mints.contains(external_id.clone()) {
+            return Ok(vec![]);
+        }
+
+        let id = self.next_token_id()?;
+        let events = self.mint_with_attributes(caller, account, attributes)?;
+        self.external_mints.insert(external_id.clone(), &id);
+        self.external_mint_ids.insert(id, &external_id);
+        Ok(events)
+    }
+
+    /// Mints a new token with `attributes` to `account` on behalf of
+    /// `caller`, calling the `before_mint` and `after_mint` hooks of
+    /// `hooks` around the mint. The mint window, allowlist and cooldown
+    /// apply to `caller`.
+    ///
+    /// The `default_attributes` whose key isn't in `attributes` are set
+    /// as well, with an `AttributeSet` event each.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MintNotActive` error if the current block is outside the
+    /// mint window.
+    ///
+    /// Returns `NotAllowlisted` error if minting is restricted to the
+    /// allowlist and `caller` has no allowance left.
+    ///
+    /// Returns `MintCooldown` error if `caller` minted less than
+    /// `mint_cooldown_blocks` blocks ago.
+    ///
+    /// Returns `BatchTooLarge` error if the `attributes` together with the
+    /// defaults they don't override are more than `max_batch_size`.
+    ///
+    /// Returns any error raised by the hooks.
+    pub fn mint_with_hooks<H: PSP34MintHooks>(
+        &mut self,
+        hooks: &mut H,
+        caller: AccountId,
+        account: AccountId,
+        attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        self.ensure_mint_active()?;
+
+        let defaults: Vec<_> = self
+            .default_attributes
+            .iter()
+            .filter(|(key, _)| !attributes.iter().any(|(k, _)| k == key))
+            .cloned()
+            .collect();
+        self.ensure_batch_size(defaults.len() + attributes.len())?;
+
+        self.consume_allowlist(caller)?;
+        self.consume_mint_cooldown(caller)?;
+
+        let id = self.take_next_token_id()?;
+
+        let attributes = defaults.iter().cloned().chain(attributes).collect();
+
+        hooks.before_mint(account, &id)?;
+        let mut events = self.mint_with_id(account, id.clone(), attributes)?;
+        hooks.after_mint(account, &id)?;
+
+        let default_events = defaults
+            .into_iter()
+            .map(|(key, data)| PSP34Event::AttributeSet {
+                id: id.clone(),
+                key,
+                data,
+            })
+            .collect();
+        events.extend(self.record(default_events));
+
+        Ok(events)
+    }
+
+    /// Returns the hash a voucher for `mint_voucher` must be signed over,
+    /// i.e. the Blake2x256 hash of the SCALE encoded `(b"PSP34Voucher",
+    /// collection_id, to, id, h)` where `h` is the Blake2x256 hash of the
+    /// SCALE encoded `attributes`.
+    ///
+    /// As for `permit_hash`, the type tag and the collection id keep a
+    /// voucher from being redeemed on another collection.
+    pub fn voucher_hash(
+        &self,
+        to: AccountId,
+        id: Id,
+        attributes: &[(Vec<u8>, Vec<u8>)],
+    ) -> [u8; 32] {
+        let mut attributes_hash = [0u8; 32];
+        hash_bytes::<Blake2x256>(&attributes.encode(), &mut attributes_hash);
+
+        let payload = (
+            b"PSP34Voucher",
+            self.collection_id(),
+            to,
+            id,
+            attributes_hash,
+        );
+
+        let mut message_hash = [0u8; 32];
+        hash_bytes::<Blake2x256>(&payload.encode(), &mut message_hash);
+        message_hash
+    }
+
+    /// Returns `true` if the voucher of hash `voucher_hash` was already
+    /// redeemed by `mint_voucher`.
+    pub fn is_voucher_used(&self, voucher_hash: [u8; 32]) -> bool {
+        self.used_vouchers.get(voucher_hash).unwrap_or(false)
+    }
+
+    /// Mints the `id` token with `attributes` to `to` from a voucher
+    /// pre-signed by `signer`, letting collectors pay for the mint
+    /// themselves instead of the studio minting everything upfront.
+    ///
+    /// `signature` is the ECDSA signature of `voucher_hash(to, id,
+    /// attributes)`, and `signer` the account derived from the signing key
+    /// (the Blake2x256 hash of its compressed public key). A voucher can
+    /// be used once, even after its token is burnt.
+    ///
+    /// # Errors
+    ///
+    /// Returns `VoucherUsed` error if the voucher was already redeemed.
+    ///
+    /// Returns `InvalidSignature` error if `signature` wasn't made by `signer`
+    /// over this voucher.
+    ///
+    /// Returns `TokenExists` error if `id` was already minted.
+    pub fn mint_voucher(
+        &mut self,
+        signer: AccountId,
+        to: AccountId,
+        id: Id,
+        attributes: Vec<(Vec<u8>, Vec<u8>)>,
+        signature: [u8; 65],
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let message_hash = self.voucher_hash(to, id.clone(), &attributes);
+        if self.is_voucher_used(message_hash) {
+            return Err(PSP34Error::VoucherUsed);
+        }
+
+        PSP34Data::ensure_signed_by(signer, &message_hash, &signature)?;
+
+        let events = self.mint_with_id(to, id, attributes)?;
+        self.used_vouchers.insert(message_hash, &true);
+        Ok(events)
+    }
+
+    /// Returns the hash a permit for `burn_permit` must be signed over,
+    /// i.e. the Blake2x256 hash of the SCALE encoded `(b"PSP34BurnPermit",
+    /// collection_id, id, nonce)`.
+    ///
+    /// As for `permit_hash`, the type tag and the collection id keep a
+    /// burn permit from being replayed on another collection.
+    pub fn burn_permit_hash(&self, id: Id, nonce: u64) -> [u8; 32] {
+        let payload = (b"PSP34BurnPermit", self.collection_id(), id, nonce);
+
+        let mut message_hash = [0u8; 32];
+        hash_bytes::<Blake2x256>(&payload.encode(), &mut message_hash);
+        message_hash
+    }
+
+    /// Returns `true` if `owner` already used `nonce` for a burn permit.
+    pub fn is_burn_nonce_used(&self, owner: AccountId, nonce: u64) -> bool {
+        self.used_burn_nonces.get((owner, nonce)).unwrap_or(false)
+    }
+
+    /// Burns the `id` token of `owner` from a permit they signed off-chain,
+    /// e.g. to let a backend redeem a voucher token on their behalf.
+    ///
+    /// `signature` is the ECDSA signature of `burn_permit_hash(id, nonce)`
+    /// made by the key `owner` is derived from, see `mint_voucher`. Each
+    /// `nonce` can be used once per owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NonceUsed` error if `owner` already used `nonce`.
+    ///
+    /// Returns `InvalidSignature` error if `signature` wasn't made by
+    /// `owner` over this permit.
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `IncorrectOwner` error if `owner` doesn't own `id`.
+    pub fn burn_permit(
+        &mut self,
+        owner: AccountId,
+        id: Id,
+        nonce: u64,
+        signature: [u8; 65],
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if self.is_burn_nonce_used(owner, nonce) {
+            return Err(PSP34Error::NonceUsed);
+        }
+
+        let message_hash = self.burn_permit_hash(id.clone(), nonce);
+        PSP34Data::ensure_signed_by(owner, &message_hash, &signature)?;
+
+        match self.owner_of(id.clone()) {
+            None => return Err(PSP34Error::TokenNotExists),
+            Some(token_owner) if token_owner != owner => return Err(PSP34Error::IncorrectOwner),
+            _ => {}
+        }
+
+        self.used_burn_nonces.insert((owner, nonce), &true);
+        self.burn(owner, id)
+    }
+
+    /// Returns the hash an approval permit for `permit` must be signed
+    /// over, i.e. the Blake2x256 hash of the SCALE encoded `(b"PSP34Permit",
+    /// collection_id, owner, operator, approved, deadline, nonce)`.
+    ///
+    /// The type tag and the collection id scope the signature to approval
+    /// permits of this collection, as the domain of EIP-712 does.
+    pub fn permit_hash(
+        &self,
+        owner: AccountId,
+        operator: AccountId,
+        approved: bool,
+        deadline: u64,
+        nonce: u64,
+    ) -> [u8; 32] {
+        let payload = (
+            b"PSP34Permit",
+            self.collection_id(),
+            owner,
+            operator,
+            approved,
+            deadline,
+            nonce,
+        );
+
+        let mut message_hash = [0u8; 32];
+        hash_bytes::<Blake2x256>(&payload.encode(), &mut message_hash);
+        message_hash
+    }
+
+    /// Returns `true` if `owner` already used `nonce` for an approval
+    /// permit.
+    pub fn is_permit_nonce_used(&self, owner: AccountId, nonce: u64) -> bool {
+        self.used_permit_nonces.get((owner, nonce)).unwrap_or(false)
+    }
+
+    /// Approves or disapproves `operator` for all the tokens of `owner`
+    /// from a permit they signed off-chain, letting a dApp submit the
+    /// approval without `owner` sending a transaction.
+    ///
+    /// `signature` is the ECDSA signature of `permit_hash(owner, operator,
+    /// approved, deadline, nonce)` made by the key `owner` is derived
+    /// from, see `mint_voucher`. The permit is valid up to the `deadline`
+    /// block included, and each `nonce` can be used once per owner.
+    ///
+    /// An `Approval` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermitExpired` error if the current block is past
+    /// `deadline`.
+    ///
+    /// Returns `NonceUsed` error if `owner` already used `nonce`.
+    ///
+    /// Returns `InvalidSignature` error if `signature` wasn't made by
+    /// `owner` over this permit.
+    ///
+    /// Returns `SelfApprove` error if `owner` is `operator`.
+    pub fn permit(
+        &mut self,
+        owner: AccountId,
+        operator: AccountId,
+        approved: bool,
+        deadline: u64,
+        nonce: u64,
+        signature: [u8; 65],
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let block_number = ink::env::block_number::<DefaultEnvironment>() as u64;
+        if block_number > deadline {
+            return Err(PSP34Error::PermitExpired);
+        }
+
+        if self.is_permit_nonce_used(owner, nonce) {
+            return Err(PSP34Error::NonceUsed);
+        }
+
+        let message_hash = self.permit_hash(owner, operator, approved, deadline, nonce);
+        PSP34Data::ensure_signed_by(owner, &message_hash, &signature)?;
+
+        self.used_permit_nonces.insert((owner, nonce), &true);
+        self.approve(owner, operator, None, approved)
+    }
+
+    /// Checks that `signature` is the ECDSA signature of `message_hash` by
+    /// the key `signer` is derived from, i.e. the Blake2x256 hash of its
+    /// compressed public key.
+    fn ensure_signed_by(
+        signer: AccountId,
+        message_hash: &[u8; 32],
+        signature: &[u8; 65],
+    ) -> PSP34Result {
+        let mut public_key = [0u8; 33];
+        ecdsa_recover(signature, message_hash, &mut public_key)
+            .map_err(|_| PSP34Error::InvalidSignature)?;
+
+        let mut recovered = [0u8; 32];
+        hash_bytes::<Blake2x256>(&public_key, &mut recovered);
+
+        if AccountId::from(recovered) != signer {
+            return Err(PSP34Error::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    /// Mints the token of a caller-chosen `id`, e.g. to mirror the id of
+    /// a token held in another collection.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidId` error if `id` is an empty `Id::Bytes`.
+    ///
+    /// Returns `TokenExists` error if `id` was already minted.
+    ///
+    /// Returns `ReachedMaxSupply` error if no supply is left outside of the
+    /// reserve.
+    ///
+    /// Returns `BatchTooLarge` error if more than `max_batch_size`
+    /// `attributes` are given.
+    ///
+    /// Returns `AttributeTooLong` error if any of the `attributes` is too
+    /// long.
+    pub fn mint_with_id(
+        &mut self,
+        account: AccountId,
+        id: Id,
+        attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        if matches!(id, Id::Bytes(ref bytes) if bytes.is_empty()) {
+            return Err(PSP34Error::InvalidId);
+        }
+
+        if self.exists(id.clone()) {
+            return Err(PSP34Error::TokenExists);
+        }
+
+        if self
+            .max_supply
+            .is_some_and(|max| self.total_supply.saturating_add(self.reserved_supply) >= max)
+        {
+            return Err(PSP34Error::ReachedMaxSupply);
+        }
+
+        self.ensure_batch_size(attributes.len())?;
+        for (key, value) in &attributes {
+            self.ensure_attribute_len(key, value)?;
+        }
+
+        self.total_supply += 1;
+        self.lifetime_minted += 1;
+
+        self.add_token(id.clone())?;
+
+        self.add_token_to(account, id.clone())?;
+
+        for (key, value) in &attributes {
+            self.attributes.insert((id.clone(), key.clone()), value);
+            self.index_attribute_key(id.clone(), key);
+        }
+
+        let mut events = vec![PSP34Event::Transfer {
+            from: None,
+            to: Some(account),
+            id: id.clone(),
+            operator: None,
+        }];
+
+        if !self.sold_out_emitted && self.max_supply == Some(self.total_supply) {
+            self.sold_out_emitted = true;
+            events.push(PSP34Event::SoldOut {
+                total_supply: self.total_supply,
+            });
+        }
+
+        Ok(self.record(events))
+    }
+}
+
+/// Quotes `bytes` as a JSON string, escaping it as needed
+#[cfg(feature = "data-uri")]
+fn json_string(bytes: &[u8]) -> String {
+    let mut quoted = String::from("\"");
+    for c in String::from_utf8_lossy(bytes).chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Encodes `bytes` as padded standard base64
+#[cfg(feature = "data-uri")]
+fn base64_encode(bytes: &[u8]) -> Vec<u8> {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        encoded.push(ALPHABET[(n >> 18) as usize & 63]);
+        encoded.push(ALPHABET[(n >> 12) as usize & 63]);
+        encoded.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63]
+        } else {
+            b'='
+        });
+        encoded.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63]
+        } else {
+            b'='
+        });
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(n: u8) -> AccountId {
+        AccountId::from([n; 32])
+    }
+
+    /// Signs `message_hash` with the `n` secret key, returning the
+    /// signature and the account of its signer
+    fn sign(n: u8, message_hash: &[u8; 32]) -> ([u8; 65], AccountId) {
+        use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+
+        let secret_key = SecretKey::from_slice(&[n; 32]).unwrap();
+        let message = Message::from_slice(message_hash).unwrap();
+        let (recovery_id, compact) = SECP256K1
+            .sign_ecdsa_recoverable(&message, &secret_key)
+            .serialize_compact();
+
+        let mut signature = [0u8; 65];
+        signature[..64].copy_from_slice(&compact);
+        signature[64] = recovery_id.to_i32() as u8;
+
+        let public_key = PublicKey::from_secret_key(SECP256K1, &secret_key).serialize();
+        let mut signer = [0u8; 32];
+        hash_bytes::<Blake2x256>(&public_key, &mut signer);
+
+        (signature, AccountId::from(signer))
+    }
+
+    #[ink::test]
+    fn mint_after_burn_gets_a_fresh_id() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+
+        for _ in 0..3 {
+            data.mint(alice, alice).unwrap();
+        }
+        data.burn(alice, Id::U128(0)).unwrap();
+
+        assert_eq!(data.next_token_id(), Ok(Id::U128(3)));
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.owner_of(Id::U128(3)), Some(alice));
+        assert_eq!(data.balance_of(alice), 3);
+    }
+
+    #[ink::test]
+    fn next_token_id_skips_ids_taken_by_mint_with_id() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+
+        data.mint_with_id(alice, Id::U128(0), vec![]).unwrap();
+        data.mint_with_id(alice, Id::U128(1), vec![]).unwrap();
+
+        assert_eq!(data.next_token_id(), Ok(Id::U128(2)));
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.owner_of(Id::U128(2)), Some(alice));
+    }
+
+    #[ink::test]
+    fn swap_of_a_middle_id_mints_a_fresh_id() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+
+        for _ in 0..3 {
+            data.mint(alice, alice).unwrap();
+        }
+
+        let events = data
+            .swap(alice, Id::U128(1), vec![(b"level".to_vec(), b"2".to_vec())])
+            .unwrap();
+
+        assert_eq!(data.owner_of(Id::U128(1)), None);
+        assert_eq!(data.owner_of(Id::U128(3)), Some(alice));
+        assert_eq!(
+            data.get_attribute(Id::U128(3), b"level".to_vec()),
+            Some(b"2".to_vec())
+        );
+        assert_eq!(data.balance_of(alice), 3);
+        assert!(events.contains(&PSP34Event::AttributeSet {
+            id: Id::U128(3),
+            key: b"level".to_vec(),
+            data: b"2".to_vec(),
+        }));
+    }
+
+    #[ink::test]
+    fn swap_at_max_supply_keeps_the_supply() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_max_supply(Some(2)).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        data.swap(alice, Id::U128(0), vec![]).unwrap();
+
+        assert_eq!(data.total_supply(), 2);
+        assert_eq!(data.owner_of(Id::U128(2)), Some(alice));
+    }
+
+    #[ink::test]
+    fn failed_swap_at_max_supply_leaves_the_old_token() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_max_supply(Some(2)).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.set_mint_window(Some(10), None);
+
+        assert_eq!(
+            data.swap(alice, Id::U128(0), vec![]),
+            Err(PSP34Error::MintNotActive)
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+        assert_eq!(data.total_supply(), 2);
+
+        data.set_mint_window(None, None);
+        data.set_max_attribute_lens(4, 4);
+        assert_eq!(
+            data.swap(
+                alice,
+                Id::U128(0),
+                vec![(b"level".to_vec(), b"99999".to_vec())]
+            ),
+            Err(PSP34Error::AttributeTooLong)
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+        assert_eq!(data.balance_of(alice), 2);
+    }
+
+    #[ink::test]
+    fn allowlist_is_consumed_from_the_caller() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.set_allowlist_only(true);
+        data.set_allowlist(alice, 1);
+
+        assert_eq!(data.mint(bob, alice), Err(PSP34Error::NotAllowlisted));
+        assert_eq!(data.allowlist_remaining(alice), 1);
+
+        data.mint(alice, bob).unwrap();
+        assert_eq!(data.allowlist_remaining(alice), 0);
+        assert_eq!(data.balance_of(bob), 1);
+        assert_eq!(data.mint(alice, alice), Err(PSP34Error::NotAllowlisted));
+    }
+
+    #[ink::test]
+    fn mint_cooldown_applies_to_the_caller() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.set_mint_cooldown_blocks(10);
+
+        data.mint(alice, bob).unwrap();
+        assert_eq!(data.mint(alice, charlie), Err(PSP34Error::MintCooldown));
+
+        data.mint(bob, bob).unwrap();
+
+        for _ in 0..10 {
+            ink::env::test::advance_block::<DefaultEnvironment>();
+        }
+        data.mint(alice, charlie).unwrap();
+    }
+
+    #[ink::test]
+    fn burn_permit_hash_is_scoped_to_the_collection() {
+        let data = PSP34Data::new();
+        let other = PSP34Data {
+            collection_id: Some(Id::U8(1)),
+            ..PSP34Data::new()
+        };
+
+        assert_ne!(
+            data.burn_permit_hash(Id::U128(0), 0),
+            other.burn_permit_hash(Id::U128(0), 0)
+        );
+        assert_ne!(
+            data.burn_permit_hash(Id::U128(0), 0),
+            data.burn_permit_hash(Id::U128(0), 1)
+        );
+    }
+
+    #[ink::test]
+    fn voucher_hash_is_scoped_to_the_collection() {
+        let data = PSP34Data::new();
+        let other = PSP34Data {
+            collection_id: Some(Id::U8(1)),
+            ..PSP34Data::new()
+        };
+        let to = account(1);
+
+        assert_ne!(
+            data.voucher_hash(to, Id::U128(0), &[]),
+            other.voucher_hash(to, Id::U128(0), &[])
+        );
+        assert_ne!(
+            data.voucher_hash(to, Id::U128(0), &[]),
+            data.burn_permit_hash(Id::U128(0), 0)
+        );
+    }
+
+    #[ink::test]
+    fn burn_checks_the_owner_and_the_caller() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(bob, bob).unwrap();
+
+        assert_eq!(data.burn(bob, Id::U128(0)), Err(PSP34Error::IncorrectOwner));
+        assert_eq!(
+            data.burn_from(bob, alice, Id::U128(0)),
+            Err(PSP34Error::NotApproved)
+        );
+        assert_eq!(data.balance_of(bob), 1);
+
+        data.approve(alice, bob, Some(Id::U128(0)), true).unwrap();
+        data.burn_from(bob, alice, Id::U128(0)).unwrap();
+        assert_eq!(data.balance_of(alice), 0);
+        assert_eq!(data.owners_token_by_index(bob, 0), Some(Id::U128(1)));
+        assert_eq!(data.total_supply(), 1);
+    }
+
+    #[ink::test]
+    fn transfer_which_burns_counts_as_a_transfer() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.set_transfer_burn_bps(10_000).unwrap();
+
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+        assert_eq!(data.transfer_count(), 1);
+
+        data.transfer(alice, bob, Id::U128(1), vec![]).unwrap();
+        assert_eq!(data.transfer_count(), 2);
+    }
+
+    #[ink::test]
+    fn transfer_with_attribute_is_rejected_while_transfers_may_burn() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.set_transfer_burn_bps(1).unwrap();
+
+        assert_eq!(
+            data.transfer_with_attribute(
+                alice,
+                bob,
+                Id::U128(0),
+                vec![],
+                b"level".to_vec(),
+                b"2".to_vec()
+            ),
+            Err(PSP34Error::TransferMayBurn)
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+    }
+
+    #[ink::test]
+    fn approve_rejects_global_operators_and_strangers_apart() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+        data.set_global_operator(bob, true);
+
+        assert_eq!(
+            data.approve(bob, charlie, Some(Id::U128(0)), true),
+            Err(PSP34Error::NotApproved)
+        );
+        assert_eq!(
+            data.approve(charlie, bob, Some(Id::U128(0)), true),
+            Err(PSP34Error::NotOwner)
+        );
+    }
+
+    #[ink::test]
+    fn inconsistent_index_fails_cleanly() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+        data.owned_tokens_index.remove(Id::U128(0));
+
+        assert_eq!(
+            data.burn(alice, Id::U128(0)),
+            Err(PSP34Error::InconsistentIndex)
+        );
+    }
+
+    #[ink::test]
+    fn rekey_keeps_the_external_mint() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint_idempotent(alice, alice, b"order-1".to_vec(), vec![])
+            .unwrap();
+
+        data.rekey(Id::U128(0), Id::U8(7)).unwrap();
+
+        assert_eq!(data.external_mint(b"order-1".to_vec()), Some(Id::U8(7)));
+        assert_eq!(
+            data.mint_idempotent(alice, alice, b"order-1".to_vec(), vec![]),
+            Ok(vec![])
+        );
+        assert_eq!(data.total_supply(), 1);
+    }
+
+    #[ink::test]
+    fn is_approved_for_all_ignores_per_token_approvals() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+
+        data.approve(alice, bob, Some(Id::U128(0)), true).unwrap();
+        assert!(!data.is_approved_for_all(alice, bob));
+
+        data.approve(alice, bob, None, true).unwrap();
+        assert!(data.is_approved_for_all(alice, bob));
+        assert!(!data.is_approved_for_all(bob, alice));
+
+        data.approve(alice, bob, None, false).unwrap();
+        assert!(!data.is_approved_for_all(alice, bob));
+    }
+
+    #[ink::test]
+    fn holder_count_follows_mints_transfers_and_burns() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.holder_count(), 1);
+
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.holder_count(), 2);
+
+        data.transfer(bob, alice, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.holder_count(), 1);
+
+        // empties alice and fills bob at once
+        data.burn(alice, Id::U128(0)).unwrap();
+        data.transfer(alice, bob, Id::U128(1), vec![]).unwrap();
+        assert_eq!(data.holder_count(), 1);
+
+        data.burn(bob, Id::U128(1)).unwrap();
+        assert_eq!(data.holder_count(), 0);
+    }
+
+    #[ink::test]
+    fn snapshot_keeps_the_balances_before_a_transfer() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        let snapshot_id = data.snapshot();
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        data.mint(alice, bob).unwrap();
+
+        assert_eq!(data.balance_of_at(alice, snapshot_id), Ok(2));
+        assert_eq!(data.balance_of_at(bob, snapshot_id), Ok(0));
+        assert_eq!(data.total_supply_at(snapshot_id), Ok(2));
+        assert_eq!(data.balance_of(bob), 2);
+
+        let later = data.snapshot();
+        assert_eq!(data.balance_of_at(alice, later), Ok(1));
+        assert_eq!(data.balance_of_at(bob, later), Ok(2));
+        assert!(data.balance_of_at(alice, later + 1).is_err());
+    }
+
+    #[ink::test]
+    fn verify_metadata_matches_the_preimage_only() {
+        let mut data = PSP34Data::new();
+        let mut hash = [0u8; 32];
+        hash_bytes::<Blake2x256>(b"{\"name\":\"Apes\"}", &mut hash);
+
+        assert!(!data.verify_metadata(b"{\"name\":\"Apes\"}".to_vec()));
+
+        data.set_collection_metadata_hash(hash).unwrap();
+        assert_eq!(data.collection_metadata_hash(), Some(hash));
+        assert!(data.verify_metadata(b"{\"name\":\"Apes\"}".to_vec()));
+        assert!(!data.verify_metadata(b"{\"name\":\"Apez\"}".to_vec()));
+        assert!(data.set_collection_metadata_hash([0; 32]).is_err());
+    }
+
+    #[ink::test]
+    fn transfer_unchecked_matches_transfer_from() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        let state = |data: &PSP34Data| {
+            (
+                data.owner_of(Id::U128(0)),
+                data.balance_of(alice),
+                data.balance_of(bob),
+                data.owners_token_by_index(alice, 0),
+                data.owners_token_by_index(bob, 0),
+            )
+        };
+
+        data.transfer_from(alice, alice, bob, Id::U128(0), vec![])
+            .unwrap();
+        let checked = state(&data);
+        data.transfer_from(bob, bob, alice, Id::U128(0), vec![])
+            .unwrap();
+
+        data.transfer_unchecked(alice, bob, Id::U128(0)).unwrap();
+        assert_eq!(state(&data), checked);
+        assert_eq!(data.transfer_count(), 3);
+        assert_eq!(
+            data.transfer_unchecked(alice, bob, Id::U128(0)),
+            Err(PSP34Error::IncorrectOwner)
+        );
+    }
+
+    #[ink::test]
+    fn indexes_follow_the_swap_of_a_burn() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        for _ in 0..3 {
+            data.mint(alice, alice).unwrap();
+        }
+
+        data.burn(alice, Id::U128(0)).unwrap();
+
+        assert_eq!(data.index_of_token(Id::U128(0)), None);
+        assert_eq!(data.index_of_token(Id::U128(2)), Some(0));
+        assert_eq!(data.token_by_index(0), Some(Id::U128(2)));
+        assert_eq!(data.index_of_owner_token(alice, Id::U128(2)), Some(0));
+        assert_eq!(data.index_of_owner_token(alice, Id::U128(1)), Some(1));
+        assert_eq!(data.owners_token_by_index(alice, 0), Some(Id::U128(2)));
+        assert_eq!(data.index_of_owner_token(bob, Id::U128(2)), None);
+    }
+
+    #[ink::test]
+    fn custom_dead_address_is_rejected_instead_of_the_zeroed_one() {
+        let (alice, dead) = (account(1), account(0xde));
+        let zero = account(0);
+        let mut data = PSP34Data::with_dead_address(dead);
+
+        data.mint(alice, alice).unwrap();
+        assert!(matches!(
+            data.mint(alice, dead),
+            Err(PSP34Error::SafeTransferCheckFailed(_))
+        ));
+        assert!(matches!(
+            data.transfer(alice, dead, Id::U128(0), vec![]),
+            Err(PSP34Error::SafeTransferCheckFailed(_))
+        ));
+        assert_eq!(data.owner_of_or_zero(Id::U128(7)), dead);
+
+        // the zeroed account is a regular one here
+        data.transfer(alice, zero, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), Some(zero));
+    }
+
+    #[ink::test]
+    fn mint_hooks_can_reject_an_account() {
+        struct Allowlist(Vec<AccountId>);
+
+        impl PSP34MintHooks for Allowlist {
+            fn before_mint(&mut self, account: AccountId, _id: &Id) -> PSP34Result {
+                if !self.0.contains(&account) {
+                    return Err(PSP34Error::NotAllowlisted);
+                }
+                Ok(())
+            }
+        }
+
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        let mut hooks = Allowlist(vec![alice]);
+
+        data.mint_with_hooks(&mut hooks, alice, alice, vec![])
+            .unwrap();
+        assert_eq!(
+            data.mint_with_hooks(&mut hooks, alice, bob, vec![]),
+            Err(PSP34Error::NotAllowlisted)
+        );
+        assert_eq!(data.balance_of(bob), 0);
+        assert_eq!(data.total_supply(), 1);
+    }
+
+    #[ink::test]
+    fn try_get_attribute_tells_a_missing_token_from_a_missing_key() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint_with_attributes(alice, alice, vec![(b"level".to_vec(), b"1".to_vec())])
+            .unwrap();
+
+        assert_eq!(
+            data.try_get_attribute(Id::U128(0), b"level".to_vec()),
+            Ok(Some(b"1".to_vec()))
+        );
+        assert_eq!(
+            data.try_get_attribute(Id::U128(0), b"rank".to_vec()),
+            Ok(None)
+        );
+        assert_eq!(
+            data.try_get_attribute(Id::U128(1), b"level".to_vec()),
+            Err(PSP34Error::TokenNotExists)
+        );
+    }
+
+    #[ink::test]
+    fn sold_out_is_emitted_by_the_last_mint_only() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_max_supply(Some(2)).unwrap();
+        let sold_out = |events: &[PSP34Event]| {
+            events
+                .iter()
+                .any(|event| matches!(event, PSP34Event::SoldOut { .. }))
+        };
+
+        let events = data.mint(alice, alice).unwrap();
+        assert!(!sold_out(&events));
+
+        let events = data.mint(alice, alice).unwrap();
+        assert!(events.contains(&PSP34Event::SoldOut { total_supply: 2 }));
+
+        // burning and minting again doesn't sell out twice
+        data.burn(alice, Id::U128(0)).unwrap();
+        let events = data.mint(alice, alice).unwrap();
+        assert!(!sold_out(&events));
+    }
+
+    #[ink::test]
+    fn typed_attributes_round_trip() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+
+        data.set_attribute_u128(Id::U128(0), b"power".to_vec(), u128::MAX)
+            .unwrap();
+        data.set_attribute_string(Id::U128(0), b"name".to_vec(), "Ape".into())
+            .unwrap();
+
+        assert_eq!(
+            data.get_attribute_u128(Id::U128(0), b"power".to_vec()),
+            Some(u128::MAX)
+        );
+        assert_eq!(
+            data.get_attribute_string(Id::U128(0), b"name".to_vec()),
+            Some("Ape".into())
+        );
+        assert_eq!(
+            data.get_attribute_u128(Id::U128(0), b"speed".to_vec()),
+            None
+        );
+    }
+
+    #[ink::test]
+    fn global_operator_can_transfer_any_token() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, staking) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+        data.mint(bob, bob).unwrap();
+
+        assert_eq!(
+            data.transfer_from(staking, alice, staking, Id::U128(0), vec![]),
+            Err(PSP34Error::NotApproved)
+        );
+
+        data.set_global_operator(staking, true);
+        data.transfer_from(staking, alice, staking, Id::U128(0), vec![])
+            .unwrap();
+        data.transfer_from(staking, bob, staking, Id::U128(1), vec![])
+            .unwrap();
+        assert_eq!(data.balance_of(staking), 2);
+
+        data.set_global_operator(staking, false);
+        data.mint(bob, bob).unwrap();
+        assert_eq!(
+            data.transfer_from(staking, bob, staking, Id::U128(2), vec![]),
+            Err(PSP34Error::NotApproved)
+        );
+    }
+
+    #[ink::test]
+    fn every_id_variant_behaves_the_same() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        let ids = [
+            Id::U8(1),
+            Id::U16(1),
+            Id::U32(1),
+            Id::U64(1),
+            Id::U128(1),
+            Id::Bytes(vec![1]),
+        ];
+
+        for id in ids.clone() {
+            data.mint_with_id(alice, id.clone(), vec![(b"level".to_vec(), b"1".to_vec())])
+                .unwrap();
+        }
+        assert_eq!(data.balance_of(alice), 6);
+
+        for id in ids {
+            data.transfer(alice, bob, id.clone(), vec![]).unwrap();
+            assert_eq!(data.owner_of(id.clone()), Some(bob));
+            assert_eq!(
+                data.get_attribute(id.clone(), b"level".to_vec()),
+                Some(b"1".to_vec())
+            );
+            data.burn(bob, id.clone()).unwrap();
+            assert_eq!(data.owner_of(id), None);
+        }
+        assert_eq!(data.total_supply(), 0);
+    }
+
+    #[ink::test]
+    fn transfer_from_rejects_an_operator_as_from() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+        data.approve(alice, bob, Some(Id::U128(0)), true).unwrap();
+
+        assert_eq!(
+            data.transfer_from(bob, bob, charlie, Id::U128(0), vec![]),
+            Err(PSP34Error::IncorrectOwner)
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+
+        data.transfer_from(bob, alice, charlie, Id::U128(0), vec![])
+            .unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), Some(charlie));
+    }
+
+    #[ink::test]
+    fn attribute_chunks_are_reassembled_in_order() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+        let key = b"image".to_vec();
+
+        assert_eq!(data.get_attribute_full(Id::U128(0), key.clone()), None);
+
+        data.set_attribute_chunk(Id::U128(0), key.clone(), 2, b"</svg>".to_vec())
+            .unwrap();
+        data.set_attribute_chunk(Id::U128(0), key.clone(), 0, b"<svg>".to_vec())
+            .unwrap();
+        // the gap at chunk 1 ends the value
+        assert_eq!(
+            data.get_attribute_full(Id::U128(0), key.clone()),
+            Some(b"<svg>".to_vec())
+        );
+
+        data.set_attribute_chunk(Id::U128(0), key.clone(), 1, b"<g/>".to_vec())
+            .unwrap();
+        data.set_attribute_chunk(Id::U128(0), key.clone(), 0, b"<svg >".to_vec())
+            .unwrap();
+        assert_eq!(
+            data.get_attribute_full(Id::U128(0), key),
+            Some(b"<svg ><g/></svg>".to_vec())
+        );
+    }
+
+    #[ink::test]
+    fn operators_of_a_token_follow_grants_and_revocations() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+
+        data.approve(alice, bob, Some(Id::U128(0)), true).unwrap();
+        data.approve(alice, charlie, Some(Id::U128(0)), true)
+            .unwrap();
+        assert_eq!(
+            data.operators_for_token(alice, Id::U128(0)),
+            vec![bob, charlie]
+        );
+        assert_eq!(data.token_info(Id::U128(0)).unwrap().operator_count, 2);
+        assert_eq!(data.get_approved(Id::U128(0)), None);
+
+        data.approve(alice, bob, Some(Id::U128(0)), false).unwrap();
+        assert_eq!(data.operators_for_token(alice, Id::U128(0)), vec![charlie]);
+        assert_eq!(data.token_info(Id::U128(0)).unwrap().operator_count, 1);
+        assert_eq!(data.get_approved(Id::U128(0)), Some(charlie));
+
+        data.approve(alice, charlie, Some(Id::U128(0)), false)
+            .unwrap();
+        assert!(data.operators_for_token(alice, Id::U128(0)).is_empty());
+    }
+
+    #[ink::test]
+    fn transfer_from_tells_a_wrong_owner_from_a_missing_approval() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(
+            data.transfer_from(alice, bob, charlie, Id::U128(0), vec![]),
+            Err(PSP34Error::IncorrectOwner)
+        );
+        assert_eq!(
+            data.transfer_from(bob, alice, charlie, Id::U128(0), vec![]),
+            Err(PSP34Error::NotApproved)
+        );
+        assert_eq!(
+            data.transfer_from(alice, alice, charlie, Id::U128(1), vec![]),
+            Err(PSP34Error::TokenNotExists)
+        );
+    }
+
+    #[ink::test]
+    fn set_attributes_adds_and_overwrites_for_authorized_callers() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint_with_attributes(alice, alice, vec![(b"level".to_vec(), b"1".to_vec())])
+            .unwrap();
+
+        assert_eq!(
+            data.set_attributes(bob, Id::U128(0), vec![(b"level".to_vec(), b"9".to_vec())]),
+            Err(PSP34Error::NotApproved)
+        );
+
+        let events = data
+            .set_attributes(
+                alice,
+                Id::U128(0),
+                vec![
+                    (b"level".to_vec(), b"2".to_vec()),
+                    (b"rank".to_vec(), b"A".to_vec()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            data.get_attribute(Id::U128(0), b"level".to_vec()),
+            Some(b"2".to_vec())
+        );
+        assert_eq!(
+            data.get_attribute(Id::U128(0), b"rank".to_vec()),
+            Some(b"A".to_vec())
+        );
+        assert_eq!(data.attribute_keys(Id::U128(0)).len(), 2);
+    }
+
+    #[ink::test]
+    fn removed_attribute_is_gone_from_every_read() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint_with_attributes(
+            alice,
+            alice,
+            vec![
+                (b"level".to_vec(), b"1".to_vec()),
+                (b"rank".to_vec(), b"A".to_vec()),
+            ],
+        )
+        .unwrap();
+
+        data.remove_attribute(alice, Id::U128(0), b"level".to_vec())
+            .unwrap();
+
+        assert_eq!(data.get_attribute(Id::U128(0), b"level".to_vec()), None);
+        assert_eq!(data.attribute_keys(Id::U128(0)), vec![b"rank".to_vec()]);
+        assert_eq!(
+            data.all_attributes(Id::U128(0)),
+            vec![(b"rank".to_vec(), b"A".to_vec())]
+        );
+        assert_eq!(
+            data.token_info(Id::U128(0)).unwrap().attribute_keys.len(),
+            1
+        );
+    }
+
+    #[ink::test]
+    fn drained_events_come_in_operation_order() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.set_event_buffering(true);
+
+        let mut returned = data.mint(alice, alice).unwrap();
+        returned.extend(data.approve(alice, bob, None, true).unwrap());
+        returned.extend(data.transfer(alice, bob, Id::U128(0), vec![]).unwrap());
+
+        let drained = data.drain_events();
+        assert_eq!(drained, returned);
+        assert!(matches!(
+            drained[0],
+            PSP34Event::Transfer { from: None, .. }
+        ));
+        assert!(matches!(drained[1], PSP34Event::Approval { .. }));
+        assert!(matches!(
+            drained[2],
+            PSP34Event::Transfer { from: Some(_), .. }
+        ));
+        assert!(data.drain_events().is_empty());
+    }
+
+    #[ink::test]
+    fn remaining_supply_at_empty_partial_and_full() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        assert_eq!(data.remaining_supply(), Balance::MAX);
+        assert_eq!(data.cap(), Balance::MAX);
+
+        data.set_max_supply(Some(2)).unwrap();
+        assert_eq!(data.remaining_supply(), 2);
+
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.remaining_supply(), 1);
+
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.remaining_supply(), 0);
+        assert_eq!(data.cap(), 2);
+    }
+
+    #[ink::test]
+    fn self_approval_is_rejected_for_all_tokens_too() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(
+            data.approve(alice, alice, None, true),
+            Err(PSP34Error::SelfApprove)
+        );
+        assert_eq!(
+            data.approve(alice, alice, Some(Id::U128(0)), true),
+            Err(PSP34Error::SelfApprove)
+        );
+        assert!(!data.is_approved_for_all(alice, alice));
+    }
+
+    #[ink::test]
+    fn constructors_set_the_current_storage_version() {
+        assert_eq!(PSP34Data::new().storage_version(), STORAGE_VERSION);
+        assert_eq!(
+            PSP34Data::new_with_config(PSP34Config::default()).storage_version(),
+            STORAGE_VERSION
+        );
+    }
+
+    #[ink::test]
+    fn voucher_mints_only_with_the_signer_signature() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        let attributes = vec![(b"level".to_vec(), b"1".to_vec())];
+        let hash = data.voucher_hash(alice, Id::U128(7), &attributes);
+        let (signature, studio) = sign(1, &hash);
+        let (forged, _) = sign(2, &hash);
+
+        assert_eq!(
+            data.mint_voucher(studio, alice, Id::U128(7), attributes.clone(), forged),
+            Err(PSP34Error::InvalidSignature)
+        );
+        assert_eq!(
+            data.mint_voucher(studio, alice, Id::U128(8), attributes.clone(), signature),
+            Err(PSP34Error::InvalidSignature)
+        );
+
+        data.mint_voucher(studio, alice, Id::U128(7), attributes.clone(), signature)
+            .unwrap();
+        assert_eq!(data.owner_of(Id::U128(7)), Some(alice));
+        assert_eq!(
+            data.mint_voucher(studio, alice, Id::U128(7), attributes, signature),
+            Err(PSP34Error::VoucherUsed)
+        );
+    }
+
+    #[ink::test]
+    fn voucher_cant_be_replayed_after_its_token_is_burnt() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        let hash = data.voucher_hash(alice, Id::U128(7), &[]);
+        let (signature, studio) = sign(1, &hash);
+
+        data.mint_voucher(studio, alice, Id::U128(7), vec![], signature)
+            .unwrap();
+        assert!(data.is_voucher_used(hash));
+        data.burn(alice, Id::U128(7)).unwrap();
+
+        assert_eq!(
+            data.mint_voucher(studio, alice, Id::U128(7), vec![], signature),
+            Err(PSP34Error::VoucherUsed)
+        );
+        assert_eq!(data.owner_of(Id::U128(7)), None);
+    }
+
+    #[ink::test]
+    fn approval_status_reflects_grants_and_revocations() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        let status = |all| ApprovalStatus { all, expiry: None };
+
+        assert_eq!(data.approval_status(alice, bob), status(false));
+        data.approve(alice, bob, None, true).unwrap();
+        assert_eq!(data.approval_status(alice, bob), status(true));
+        data.approve(alice, bob, None, false).unwrap();
+        assert_eq!(data.approval_status(alice, bob), status(false));
+    }
+
+    #[ink::test]
+    fn owners_of_mixes_minted_and_unminted_ids() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, bob).unwrap();
+
+        assert_eq!(
+            data.owners_of(vec![Id::U128(1), Id::U128(5), Id::U128(0)]),
+            Ok(vec![Some(bob), None, Some(alice)])
+        );
+        assert_eq!(data.owners_of(vec![]), Ok(vec![]));
+    }
+
+    #[ink::test]
+    fn empty_bytes_id_is_rejected() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+
+        assert_eq!(
+            data.mint_with_id(alice, Id::Bytes(vec![]), vec![]),
+            Err(PSP34Error::InvalidId)
+        );
+        data.mint_with_id(alice, Id::Bytes(vec![0]), vec![])
+            .unwrap();
+        assert_eq!(data.owner_of(Id::Bytes(vec![0])), Some(alice));
+        assert_eq!(data.total_supply(), 1);
+    }
+
+    #[ink::test]
+    fn last_transfer_follows_the_mint_and_transfers() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.last_transfer_of(Id::U128(0)), Some(0));
+
+        for _ in 0..3 {
+            ink::env::test::advance_block::<DefaultEnvironment>();
+        }
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+
+        assert_eq!(data.last_transfer_of(Id::U128(0)), Some(3));
+        assert_eq!(data.last_transfer_of(Id::U128(1)), None);
+    }
+
+    #[ink::test]
+    fn transfer_count_skips_mints_and_counts_self_transfers() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.transfer_count(), 0);
+
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.transfer_count(), 1);
+
+        data.transfer(bob, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.transfer_count(), 2);
+        assert_eq!(data.owner_of(Id::U128(0)), Some(bob));
+
+        data.burn(bob, Id::U128(0)).unwrap();
+        assert_eq!(data.transfer_count(), 2);
+    }
+
+    #[ink::test]
+    fn swap_enumeration_moves_the_last_token_into_a_burnt_slot() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        for _ in 0..4 {
+            data.mint(alice, alice).unwrap();
+        }
+
+        data.burn(alice, Id::U128(1)).unwrap();
+
+        let tokens: Vec<_> = (0..4).map(|index| data.token_by_index(index)).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Some(Id::U128(0)),
+                Some(Id::U128(3)),
+                Some(Id::U128(2)),
+                None
+            ]
+        );
+    }
+
+    #[ink::test]
+    fn stable_enumeration_keeps_the_order_after_a_burn() {
+        let mut data = PSP34Data::with_stable_enumeration();
+        let alice = account(1);
+        for _ in 0..4 {
+            data.mint(alice, alice).unwrap();
+        }
+
+        data.burn(alice, Id::U128(1)).unwrap();
+
+        let tokens: Vec<_> = (0..4).map(|index| data.token_by_index(index)).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Some(Id::U128(0)),
+                None,
+                Some(Id::U128(2)),
+                Some(Id::U128(3))
+            ]
+        );
+        assert_eq!(data.total_supply(), 3);
+    }
+
+    #[ink::test]
+    fn huge_indexes_return_none() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(data.token_by_index(u128::MAX), None);
+        assert_eq!(data.owners_token_by_index(alice, u128::MAX), None);
+        assert_eq!(data.next_existing_token(u128::MAX), None);
+    }
+
+    #[ink::test]
+    fn reused_id_starts_without_approvals() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint_with_id(alice, Id::U8(1), vec![]).unwrap();
+        data.approve(alice, bob, Some(Id::U8(1)), true).unwrap();
+
+        data.burn(alice, Id::U8(1)).unwrap();
+        data.mint_with_id(alice, Id::U8(1), vec![]).unwrap();
+
+        assert!(!data.allowance(alice, bob, Some(Id::U8(1))));
+        assert!(data.operators_for_token(alice, Id::U8(1)).is_empty());
+        assert_eq!(
+            data.transfer_from(bob, alice, bob, Id::U8(1), vec![]),
+            Err(PSP34Error::NotApproved)
+        );
+    }
+
+    #[ink::test]
+    fn batches_up_to_the_limit_pass() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.set_max_batch_size(2);
+        data.mint(alice, alice).unwrap();
+        let ids = |n| (0..n).map(Id::U128).collect::<Vec<_>>();
+
+        assert!(data.owners_of(ids(2)).is_ok());
+        assert_eq!(data.owners_of(ids(3)), Err(PSP34Error::BatchTooLarge));
+        assert!(data.allowance_batch(alice, bob, ids(2)).is_ok());
+        assert_eq!(
+            data.allowance_batch(alice, bob, ids(3)),
+            Err(PSP34Error::BatchTooLarge)
+        );
+        assert_eq!(
+            data.get_attribute_for_tokens(ids(3), b"level".to_vec()),
+            Err(PSP34Error::BatchTooLarge)
+        );
+
+        assert_eq!(
+            data.airdrop(vec![alice, bob, bob]),
+            Err(PSP34Error::BatchTooLarge)
+        );
+        data.airdrop(vec![alice, bob]).unwrap();
+        assert_eq!(data.total_supply(), 3);
+    }
+
+    #[ink::test]
+    fn mint_is_only_allowed_inside_the_window() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_mint_window(Some(2), Some(3));
+
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        assert_eq!(data.mint(alice, alice), Err(PSP34Error::MintNotActive));
+
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        data.mint(alice, alice).unwrap();
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        data.mint(alice, alice).unwrap();
+
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        assert_eq!(data.mint(alice, alice), Err(PSP34Error::MintNotActive));
+        assert_eq!(data.total_supply(), 2);
+    }
+
+    #[ink::test]
+    fn public_phase_bypasses_the_allowlist() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.set_allowlist(alice, 1);
+
+        data.mint(bob, bob).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.allowlist_remaining(alice), 1);
+
+        data.set_allowlist_only(true);
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.allowlist_remaining(alice), 0);
+        assert_eq!(data.mint(alice, alice), Err(PSP34Error::NotAllowlisted));
+
+        data.set_allowlist_only(false);
+        data.mint(alice, alice).unwrap();
+    }
+
+    #[ink::test]
+    fn reveal_switches_to_the_final_uri_for_good() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+        data.set_reveal_uris("ipfs://hidden".into(), "ipfs://apes/".into())
+            .unwrap();
+
+        assert_eq!(data.token_uri(Id::U128(0)), Some("ipfs://hidden".into()));
+
+        data.reveal().unwrap();
+        assert_eq!(data.token_uri(Id::U128(0)), Some("ipfs://apes/0".into()));
+        assert!(data.reveal().is_err());
+        assert!(data
+            .set_reveal_uris("ipfs://hidden".into(), "ipfs://other/".into())
+            .is_err());
+        assert!(data.revealed());
+    }
+
+    #[ink::test]
+    fn locked_token_transfers_once_its_unlock_block_is_reached() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.set_transfer_unlock_block(Id::U128(0), 2).unwrap();
+
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        assert!(data.is_locked(Id::U128(0)));
+        assert_eq!(
+            data.transfer(alice, bob, Id::U128(0), vec![]),
+            Err(PSP34Error::TokenLocked)
+        );
+
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        assert!(!data.is_locked(Id::U128(0)));
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+    }
+
+    #[ink::test]
+    fn soulbound_token_can_be_burnt_but_not_transferred() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint_soulbound(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        assert!(data.is_soulbound(Id::U128(0)));
+        assert!(!data.is_soulbound(Id::U128(1)));
+        assert_eq!(
+            data.transfer(alice, bob, Id::U128(0), vec![]),
+            Err(PSP34Error::Soulbound)
+        );
+        assert_eq!(
+            data.transfer_unchecked(alice, bob, Id::U128(0)),
+            Err(PSP34Error::Soulbound)
+        );
+
+        data.burn(alice, Id::U128(0)).unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+    }
+
+    #[ink::test]
+    fn balance_cap_applies_to_transfers_and_mints() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.set_max_balance_per_owner(Some(1));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, bob).unwrap();
+
+        assert_eq!(
+            data.transfer(alice, bob, Id::U128(0), vec![]),
+            Err(PSP34Error::BalanceCapExceeded)
+        );
+        assert_eq!(data.mint(alice, bob), Err(PSP34Error::BalanceCapExceeded));
+
+        data.burn(bob, Id::U128(1)).unwrap();
+        data.mint(alice, bob).unwrap();
+        assert_eq!(data.balance_of(bob), 1);
+    }
+
+    /// Runs random mints, transfers, burns, swaps and re-keys, checking
+    /// the invariants after each of them
+    #[cfg(feature = "invariants")]
+    fn fuzz_invariants(data: &mut PSP34Data, mut seed: u64) {
+        let mut next = |bound: u64| {
+            // xorshift64, enough to shuffle the operations around
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % bound
+        };
+
+        for step in 0..500u64 {
+            let account_a = account(1 + next(4) as u8);
+            let account_b = account(1 + next(4) as u8);
+            let live = data.next_existing_token(next(data.all_tokens.len() as u64 + 1) as u128);
+
+            match (next(5), live) {
+                (1, Some((_, id))) => {
+                    let owner = data.owner_of(id.clone()).unwrap();
+                    data.transfer(owner, account_b, id, vec![]).unwrap();
+                }
+                (2, Some((_, id))) => {
+                    let owner = data.owner_of(id.clone()).unwrap();
+                    data.burn(owner, id).unwrap();
+                }
+                (3, Some((_, id))) => {
+                    let owner = data.owner_of(id.clone()).unwrap();
+                    data.swap(owner, id, vec![]).unwrap();
+                }
+                (4, Some((_, id))) => {
+                    data.rekey(id, Id::U64(step)).unwrap();
+                }
+                _ => {
+                    data.mint(account_a, account_b).unwrap();
+                }
+            }
+
+            assert_eq!(data.check_invariants(), Ok(()), "step {}", step);
+        }
+    }
+
+    #[cfg(feature = "invariants")]
+    #[ink::test]
+    fn random_operations_keep_the_invariants() {
+        let mut data = PSP34Data::new();
+        fuzz_invariants(&mut data, 0x5eed);
+        assert!(data.total_supply() > 0);
+    }
+
+    #[cfg(feature = "invariants")]
+    #[ink::test]
+    fn random_operations_keep_the_invariants_of_stable_enumeration() {
+        let mut data = PSP34Data::with_stable_enumeration();
+        fuzz_invariants(&mut data, 0xa9e5);
+        assert!(data.total_supply() > 0);
+    }
+
+    #[ink::test]
+    fn owner_of_or_zero_falls_back_to_the_zero_address() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(data.owner_of_or_zero(Id::U128(0)), alice);
+        assert_eq!(data.owner_of_or_zero(Id::U128(1)), account(0));
+    }
+
+    #[ink::test]
+    fn partial_config_keeps_the_other_defaults() {
+        let defaults = PSP34Data::new();
+        let data = PSP34Data::new_with_config(PSP34Config {
+            max_supply: Some(10),
+            mint_price: Some(5),
+            ..PSP34Config::default()
+        });
+
+        assert_eq!(data.max_supply(), Some(10));
+        assert_eq!(data.mint_price(), 5);
+        assert_eq!(data.max_batch_size(), defaults.max_batch_size());
+        assert_eq!(data.mint_cooldown_blocks(), defaults.mint_cooldown_blocks());
+        assert_eq!(data.well_known_keys(), WellKnownKeys::default());
+        assert_eq!(data.royalty_info(100), None);
+    }
+
+    #[ink::test]
+    fn transfer_with_attribute_writes_only_on_success() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(
+            data.transfer_with_attribute(
+                bob,
+                alice,
+                Id::U128(0),
+                vec![],
+                b"sold".to_vec(),
+                b"1".to_vec()
+            ),
+            Err(PSP34Error::NotApproved)
+        );
+        assert_eq!(data.get_attribute(Id::U128(0), b"sold".to_vec()), None);
+
+        let events = data
+            .transfer_with_attribute(
+                alice,
+                bob,
+                Id::U128(0),
+                vec![],
+                b"sold".to_vec(),
+                b"1".to_vec(),
+            )
+            .unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), Some(bob));
+        assert_eq!(
+            data.get_attribute(Id::U128(0), b"sold".to_vec()),
+            Some(b"1".to_vec())
+        );
+        assert!(matches!(events[0], PSP34Event::Transfer { .. }));
+        assert!(matches!(events[1], PSP34Event::AttributeSet { .. }));
+    }
+
+    #[ink::test]
+    fn attributes_up_to_the_length_limits_pass() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+        data.set_max_attribute_lens(4, 8);
+
+        data.set_attribute(Id::U128(0), vec![b'k'; 4], vec![b'v'; 8])
+            .unwrap();
+        assert_eq!(
+            data.set_attribute(Id::U128(0), vec![b'k'; 5], vec![b'v'; 8]),
+            Err(PSP34Error::AttributeTooLong)
+        );
+        assert_eq!(
+            data.set_attribute(Id::U128(0), vec![b'k'; 4], vec![b'v'; 9]),
+            Err(PSP34Error::AttributeTooLong)
+        );
+        assert_eq!(
+            data.get_attribute(Id::U128(0), vec![b'k'; 4]),
+            Some(vec![b'v'; 8])
+        );
+    }
+
+    #[ink::test]
+    fn collection_id_defaults_to_the_contract_account() {
+        let contract = account(9);
+        ink::env::test::set_callee::<DefaultEnvironment>(contract);
+
+        assert_eq!(PSP34Data::new().collection_id(), Id::Bytes(vec![9; 32]));
+        let data = PSP34Data::new_with_config(PSP34Config {
+            collection_id: Some(Id::U8(1)),
+            ..PSP34Config::default()
+        });
+        assert_eq!(data.collection_id(), Id::U8(1));
+    }
+
+    #[ink::test]
+    fn proceeds_and_royalties_are_withdrawn_separately() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_mint_price(30);
+        data.mint_with_payment(alice, alice, 30).unwrap();
+        data.add_royalty_escrow(20);
+        let balance = 50;
+
+        assert_eq!(data.take_mint_proceeds(balance), 30);
+        assert_eq!(data.royalty_escrow(), 20);
+        assert_eq!(data.take_mint_proceeds(balance - 30), 0);
+
+        assert_eq!(data.take_royalty_escrow(balance - 30), 20);
+        assert_eq!(data.take_royalty_escrow(0), 0);
+        assert_eq!(data.mint_proceeds(), 0);
+    }
+
+    #[ink::test]
+    fn token_info_matches_the_individual_getters() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint_with_attributes(alice, alice, vec![(b"level".to_vec(), b"1".to_vec())])
+            .unwrap();
+        data.approve(alice, bob, Some(Id::U128(0)), true).unwrap();
+
+        assert_eq!(
+            data.token_info(Id::U128(0)),
+            Some(TokenInfo {
+                owner: data.owner_of(Id::U128(0)).unwrap(),
+                exists: true,
+                approved_operators: data.operators_for_token(alice, Id::U128(0)),
+                operator_count: 1,
+                attribute_keys: data.attribute_keys(Id::U128(0)),
+                attribute_count: 1,
+            })
+        );
+        assert_eq!(data.token_info(Id::U128(1)), None);
+    }
+
+    #[ink::test]
+    fn random_traits_are_deterministic_and_cover_every_pool() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        let pools = vec![
+            (b"fur".to_vec(), vec![b"gold".to_vec(), b"brown".to_vec()]),
+            (
+                b"eyes".to_vec(),
+                vec![b"red".to_vec(), b"blue".to_vec(), b"x".to_vec()],
+            ),
+        ];
+
+        let expected = PSP34Data::random_attributes(0, &Id::U128(0), &pools);
+        assert_eq!(
+            expected,
+            PSP34Data::random_attributes(0, &Id::U128(0), &pools)
+        );
+
+        data.mint_random(alice, alice, pools.clone()).unwrap();
+        for ((key, options), (expected_key, value)) in pools.iter().zip(&expected) {
+            assert_eq!(key, expected_key);
+            assert!(options.contains(value));
+            assert_eq!(
+                data.get_attribute(Id::U128(0), key.clone()),
+                Some(value.clone())
+            );
+        }
+        assert_eq!(
+            data.mint_random(alice, alice, vec![(b"hat".to_vec(), vec![])]),
+            Err(PSP34Error::Custom("empty trait pool".into()))
+        );
+    }
+
+    #[ink::test]
+    fn public_mint_leaves_the_reserve_alone() {
+        let mut data = PSP34Data::new();
+        let (alice, treasury) = (account(1), account(2));
+        data.set_max_supply(Some(3)).unwrap();
+        data.set_reserved_supply(2).unwrap();
+
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.mint(alice, alice), Err(PSP34Error::ReachedMaxSupply));
+
+        // the reserve is minted regardless of the mint window
+        data.set_mint_window(Some(100), None);
+        data.reserve_mint(treasury, 2).unwrap();
+        assert_eq!(data.balance_of(treasury), 2);
+        assert_eq!(data.reserved_supply(), 0);
+        assert!(data.reserve_mint(treasury, 1).is_err());
+    }
+
+    #[ink::test]
+    fn transfers_move_delegated_votes() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, carol, dave) = (account(1), account(2), account(3), account(4));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        data.delegate(alice, carol);
+        data.delegate(bob, dave);
+        assert_eq!((data.get_votes(alice), data.get_votes(carol)), (0, 2));
+
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.get_votes(carol), 1);
+        assert_eq!(data.get_votes(dave), 1);
+        assert_eq!(data.get_votes(bob), 0);
+
+        data.delegate(alice, alice);
+        assert_eq!((data.get_votes(alice), data.get_votes(carol)), (1, 0));
+    }
+
+    #[ink::test]
+    fn burning_a_middle_u32_token_keeps_the_ids_intact() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        for n in 1..=4 {
+            data.mint_with_id(alice, Id::U32(n), vec![]).unwrap();
+        }
+
+        data.burn(alice, Id::U32(2)).unwrap();
+
+        let tokens: Vec<_> = (0..3)
+            .filter_map(|index| data.token_by_index(index))
+            .collect();
+        assert_eq!(tokens, vec![Id::U32(1), Id::U32(4), Id::U32(3)]);
+        let owned: Vec<_> = (0..3)
+            .filter_map(|index| data.owners_token_by_index(alice, index))
+            .collect();
+        assert_eq!(owned, tokens);
+        for (index, id) in tokens.into_iter().enumerate() {
+            assert_eq!(data.index_of_token(id), Some(index as u128));
+        }
+    }
+
+    #[ink::test]
+    fn airdrop_mints_to_every_recipient_or_none() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, carol) = (account(1), account(2), account(3));
+        data.set_max_supply(Some(4)).unwrap();
+
+        let events = data.airdrop(vec![alice, bob, carol]).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+        assert_eq!(data.owner_of(Id::U128(1)), Some(bob));
+        assert_eq!(data.owner_of(Id::U128(2)), Some(carol));
+
+        // two more don't fit in the cap, so neither is minted
+        assert_eq!(
+            data.airdrop(vec![alice, bob]),
+            Err(PSP34Error::ReachedMaxSupply)
+        );
+        assert_eq!(data.total_supply(), 3);
+        assert_eq!(data.balance_of(alice), 1);
+        assert_eq!(data.balance_of(bob), 1);
+    }
+
+    #[ink::test]
+    fn frozen_token_attributes_are_rejected_per_token() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        data.freeze_token_metadata(Id::U128(0)).unwrap();
+        assert!(data.is_token_metadata_frozen(Id::U128(0)));
+        assert_eq!(
+            data.set_attribute(Id::U128(0), b"name".to_vec(), b"a".to_vec()),
+            Err(PSP34Error::MetadataFrozen)
+        );
+        assert!(!data.is_token_metadata_frozen(Id::U128(1)));
+        assert!(data
+            .set_attribute(Id::U128(1), b"name".to_vec(), b"b".to_vec())
+            .is_ok());
+        assert_eq!(
+            data.freeze_token_metadata(Id::U128(2)),
+            Err(PSP34Error::TokenNotExists)
+        );
+    }
+
+    #[ink::test]
+    fn default_attributes_yield_to_explicit_ones() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_default_attributes(vec![
+            (b"rarity".to_vec(), b"common".to_vec()),
+            (b"edition".to_vec(), b"1".to_vec()),
+        ])
+        .unwrap();
+
+        data.mint(alice, alice).unwrap();
+        assert_eq!(
+            data.get_attribute(Id::U128(0), b"rarity".to_vec()),
+            Some(b"common".to_vec())
+        );
+        assert_eq!(
+            data.get_attribute(Id::U128(0), b"edition".to_vec()),
+            Some(b"1".to_vec())
+        );
+
+        data.mint_with_attributes(alice, alice, vec![(b"rarity".to_vec(), b"rare".to_vec())])
+            .unwrap();
+        assert_eq!(
+            data.get_attribute(Id::U128(1), b"rarity".to_vec()),
+            Some(b"rare".to_vec())
+        );
+        assert_eq!(
+            data.get_attribute(Id::U128(1), b"edition".to_vec()),
+            Some(b"1".to_vec())
+        );
+    }
+
+    #[ink::test]
+    fn transfer_burn_follows_its_seed() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+
+        // off by default: the token always goes through
+        for _ in 0..10 {
+            data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+            data.transfer(bob, alice, Id::U128(0), vec![]).unwrap();
+        }
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+
+        // the outcome of each transfer is known beforehand
+        data.set_transfer_burn_bps(5_000).unwrap();
+        let mut count = data.transfer_count();
+        while !PSP34Data::transfer_burns(0, &Id::U128(0), count, 5_000) {
+            let from = data.owner_of(Id::U128(0)).unwrap();
+            let to = if from == alice { bob } else { alice };
+            data.transfer(from, to, Id::U128(0), vec![]).unwrap();
+            assert_eq!(data.owner_of(Id::U128(0)), Some(to));
+            count += 1;
+        }
+        let owner = data.owner_of(Id::U128(0)).unwrap();
+        data.transfer(owner, account(3), Id::U128(0), vec![])
+            .unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+        assert_eq!(data.total_supply(), 0);
+    }
+
+    #[ink::test]
+    fn get_attribute_for_tokens_keeps_the_order_of_ids() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint_with_attributes(alice, alice, vec![(b"level".to_vec(), b"1".to_vec())])
+            .unwrap();
+        data.mint(alice, alice).unwrap();
+        data.mint_with_attributes(alice, alice, vec![(b"level".to_vec(), b"3".to_vec())])
+            .unwrap();
+
+        assert_eq!(
+            data.get_attribute_for_tokens(
+                vec![Id::U128(2), Id::U128(1), Id::U128(9), Id::U128(0)],
+                b"level".to_vec()
+            ),
+            Ok(vec![Some(b"3".to_vec()), None, None, Some(b"1".to_vec())])
+        );
+    }
+
+    #[ink::test]
+    fn stranger_is_told_apart_from_an_approved_operator() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+
+        data.mint(alice, alice).unwrap();
+
+        // no relationship to the token: the ownership check fails first
+        assert_eq!(
+            data.approve(charlie, bob, Some(Id::U128(0)), true),
+            Err(PSP34Error::NotOwner)
+        );
+
+        // an operator of another token of the owner isn't a stranger
+        data.approve(alice, charlie, Some(Id::U128(1)), true)
+            .unwrap();
+        assert_eq!(
+            data.approve(charlie, bob, Some(Id::U128(0)), true),
+            Err(PSP34Error::NotApproved)
+        );
+
+        // the approval is gone with the token, so is the relationship
+        data.burn(alice, Id::U128(1)).unwrap();
+        assert_eq!(
+            data.approve(charlie, bob, Some(Id::U128(0)), true),
+            Err(PSP34Error::NotOwner)
+        );
+
+        // an operator of the token may approve on behalf of its owner
+        data.approve(alice, charlie, Some(Id::U128(0)), true)
+            .unwrap();
+        data.approve(charlie, bob, Some(Id::U128(0)), true).unwrap();
+        assert!(data.allowance(alice, bob, Some(Id::U128(0))));
+    }
+
+    #[cfg(feature = "migration")]
+    #[ink::test]
+    fn migrate_allowances_copies_the_vec_based_approvals() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie, dave) = (account(1), account(2), account(3), account(4));
+        data.mint(alice, alice).unwrap();
+
+        let mut old = Mapping::new();
+        old.insert((alice, Some(Id::U128(0))), &vec![bob, charlie]);
+        old.insert((alice, None::<Id>), &vec![dave]);
+
+        assert_eq!(
+            data.migrate_allowances(
+                &old,
+                vec![(alice, Some(Id::U128(0))), (alice, None), (bob, None)]
+            ),
+            Ok(3)
+        );
+        assert!(data.allowance(alice, bob, Some(Id::U128(0))));
+        assert!(data.allowance(alice, charlie, Some(Id::U128(0))));
+        assert!(data.is_allowed_all(alice, dave));
+        assert!(!data.is_allowed_all(alice, bob));
+    }
+
+    #[ink::test]
+    fn owners_keep_the_minimum_balance() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.set_min_balance_to_keep(1);
+
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(
+            data.transfer(alice, bob, Id::U128(1), vec![]),
+            Err(PSP34Error::BelowMinBalance)
+        );
+        assert_eq!(data.balance_of(alice), 1);
+
+        // transferring to oneself and burning are exempt
+        data.transfer(alice, alice, Id::U128(1), vec![]).unwrap();
+        data.burn(alice, Id::U128(1)).unwrap();
+        assert_eq!(data.balance_of(alice), 0);
+    }
+
+    #[ink::test]
+    fn burn_event_is_emitted_by_burns_only() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        let is_burn = |events: &[PSP34Event]| {
+            events
+                .iter()
+                .any(|event| matches!(event, PSP34Event::Burn { .. }))
+        };
+
+        assert!(!is_burn(&data.mint(alice, alice).unwrap()));
+        assert!(!is_burn(
+            &data.transfer(alice, bob, Id::U128(0), vec![]).unwrap()
+        ));
+
+        let events = data.burn(alice, Id::U128(1)).unwrap();
+        assert!(events.contains(&PSP34Event::Burn {
+            from: alice,
+            id: Id::U128(1)
+        }));
+        assert!(events.contains(&PSP34Event::Transfer {
+            from: Some(alice),
+            to: None,
+            id: Id::U128(1),
+            operator: None,
+        }));
+    }
+
+    #[ink::test]
+    fn delegated_operators_act_one_hop_only() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie, dave) = (account(1), account(2), account(3), account(4));
+        data.mint(alice, alice).unwrap();
+        data.approve(alice, bob, None, true).unwrap();
+        data.delegate_operator(bob, charlie, true).unwrap();
+        data.delegate_operator(charlie, dave, true).unwrap();
+
+        // delegations are ignored until the collection opts in
+        assert_eq!(
+            data.transfer_from(charlie, alice, charlie, Id::U128(0), vec![]),
+            Err(PSP34Error::NotApproved)
+        );
+
+        data.set_transitive_approvals(true);
+        // dave's delegator wasn't approved by alice directly
+        assert_eq!(
+            data.transfer_from(dave, alice, dave, Id::U128(0), vec![]),
+            Err(PSP34Error::NotApproved)
+        );
+        data.transfer_from(charlie, alice, charlie, Id::U128(0), vec![])
+            .unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), Some(charlie));
+    }
+
+    #[ink::test]
+    fn all_attributes_returns_every_pair() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        let attributes = vec![
+            (b"level".to_vec(), b"1".to_vec()),
+            (b"class".to_vec(), b"mage".to_vec()),
+            (b"rarity".to_vec(), b"rare".to_vec()),
+        ];
+        data.mint_with_attributes(alice, alice, attributes.clone())
+            .unwrap();
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(data.all_attributes(Id::U128(0)), attributes);
+        assert_eq!(data.all_attributes(Id::U128(1)), vec![]);
+    }
+
+    #[ink::test]
+    fn burn_permit_burns_once_per_nonce() {
+        let mut data = PSP34Data::new();
+        let (_, owner) = sign(7, &[0; 32]);
+        data.mint(owner, owner).unwrap();
+        data.mint(owner, owner).unwrap();
+
+        let (signature, _) = sign(7, &data.burn_permit_hash(Id::U128(0), 1));
+        data.burn_permit(owner, Id::U128(0), 1, signature).unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+        assert!(data.is_burn_nonce_used(owner, 1));
+
+        // the nonce is spent, whatever the token
+        let (signature, _) = sign(7, &data.burn_permit_hash(Id::U128(1), 1));
+        assert_eq!(
+            data.burn_permit(owner, Id::U128(1), 1, signature),
+            Err(PSP34Error::NonceUsed)
+        );
+        let (signature, _) = sign(8, &data.burn_permit_hash(Id::U128(1), 2));
+        assert_eq!(
+            data.burn_permit(owner, Id::U128(1), 2, signature),
+            Err(PSP34Error::InvalidSignature)
+        );
+        assert_eq!(data.owner_of(Id::U128(1)), Some(owner));
+    }
+
+    #[ink::test]
+    fn allowance_batch_mixes_token_and_all_approvals() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        for _ in 0..3 {
+            data.mint(alice, alice).unwrap();
+        }
+        data.approve(alice, bob, Some(Id::U128(1)), true).unwrap();
+        data.approve(alice, charlie, None, true).unwrap();
+
+        let ids = vec![Id::U128(0), Id::U128(1), Id::U128(2)];
+        assert_eq!(
+            data.allowance_batch(alice, bob, ids.clone()),
+            Ok(vec![false, true, false])
+        );
+        assert_eq!(
+            data.allowance_batch(alice, charlie, ids),
+            Ok(vec![true, true, true])
+        );
+        assert_eq!(data.allowance_batch(alice, bob, vec![]), Ok(vec![]));
+    }
+
+    #[ink::test]
+    fn next_existing_token_pages_over_burnt_slots() {
+        let mut data = PSP34Data::with_stable_enumeration();
+        let alice = account(1);
+        for _ in 0..6 {
+            data.mint(alice, alice).unwrap();
+        }
+        data.burn(alice, Id::U128(1)).unwrap();
+        data.burn(alice, Id::U128(2)).unwrap();
+        data.burn(alice, Id::U128(5)).unwrap();
+
+        let mut live = Vec::new();
+        let mut from = 0;
+        while let Some((index, id)) = data.next_existing_token(from) {
+            live.push((index, id));
+            from = index + 1;
+        }
+        assert_eq!(
+            live,
+            vec![(0, Id::U128(0)), (3, Id::U128(3)), (4, Id::U128(4))]
+        );
+        assert_eq!(data.next_existing_token(u128::MAX), None);
+    }
+
+    #[ink::test]
+    fn rekey_moves_the_owner_approvals_and_attributes() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint_with_attributes(alice, alice, vec![(b"level".to_vec(), b"1".to_vec())])
+            .unwrap();
+        data.approve(alice, bob, Some(Id::U128(0)), true).unwrap();
+        let new_id = Id::Bytes(b"hash".to_vec());
+
+        data.set_attribute_chunk(Id::U128(0), b"svg".to_vec(), 0, b"<svg>".to_vec())
+            .unwrap();
+        data.set_attribute_chunk(Id::U128(0), b"svg".to_vec(), 1, b"</svg>".to_vec())
+            .unwrap();
+
+        assert_eq!(
+            data.rekey(Id::U128(0), new_id.clone()),
+            Ok(vec![PSP34Event::Rekeyed {
+                old_id: Id::U128(0),
+                new_id: new_id.clone()
+            }])
+        );
+
+        assert_eq!(
+            data.get_attribute_full(new_id.clone(), b"svg".to_vec()),
+            Some(b"<svg></svg>".to_vec())
+        );
+        // nothing is left behind for a token minted again as `old_id`
+        data.mint_with_id(bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.get_attribute_full(Id::U128(0), b"svg".to_vec()), None);
+        data.burn(bob, Id::U128(0)).unwrap();
+
+        assert_eq!(data.owner_of(new_id.clone()), Some(alice));
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+        assert_eq!(
+            data.get_attribute(new_id.clone(), b"level".to_vec()),
+            Some(b"1".to_vec())
+        );
+        assert_eq!(data.get_attribute(Id::U128(0), b"level".to_vec()), None);
+        assert!(data.allowance(alice, bob, Some(new_id.clone())));
+        assert!(!data.allowance(alice, bob, Some(Id::U128(0))));
+        assert_eq!(data.token_by_index(0), Some(new_id.clone()));
+        assert_eq!(data.owners_token_by_index(alice, 0), Some(new_id));
+    }
+
+    #[ink::test]
+    fn owners_token_by_index_stops_at_the_balance() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(data.owners_token_by_index(alice, 1), Some(Id::U128(1)));
+        assert_eq!(data.owners_token_by_index(alice, 2), None);
+        assert_eq!(data.owners_token_by_index(bob, 0), None);
+
+        // the last slot of alice is vacated, not left stale
+        data.transfer(alice, bob, Id::U128(0), vec![]).unwrap();
+        assert_eq!(data.owners_token_by_index(alice, 0), Some(Id::U128(1)));
+        assert_eq!(data.owners_token_by_index(alice, 1), None);
+        assert_eq!(data.owners_token_by_index(bob, 0), Some(Id::U128(0)));
+    }
+
+    #[ink::test]
+    fn token_uri_prefers_the_explicit_uri() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_reveal_uris("ipfs://hidden".into(), "ipfs://base/".into())
+            .unwrap();
+        data.reveal().unwrap();
+
+        data.mint_with_uri(alice, alice, b"ipfs://own".to_vec())
+            .unwrap();
+        data.mint(alice, alice).unwrap();
+
+        assert_eq!(data.token_uri(Id::U128(0)), Some("ipfs://own".into()));
+        assert_eq!(data.token_uri(Id::U128(1)), Some("ipfs://base/1".into()));
+        assert_eq!(data.token_uri(Id::U128(2)), None);
+    }
+
+    #[ink::test]
+    fn lifetime_minted_counts_burnt_tokens() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        for _ in 0..3 {
+            data.mint(alice, alice).unwrap();
+        }
+
+        data.burn(alice, Id::U128(1)).unwrap();
+
+        assert_eq!(data.lifetime_minted(), 3);
+        assert_eq!(data.total_supply(), 2);
+    }
+
+    #[ink::test]
+    fn permit_approves_until_its_deadline_and_once() {
+        let mut data = PSP34Data::new();
+        let bob = account(2);
+        let (_, owner) = sign(7, &[0; 32]);
+
+        let (signature, _) = sign(7, &data.permit_hash(owner, bob, true, 1, 0));
+        data.permit(owner, bob, true, 1, 0, signature).unwrap();
+        assert!(data.is_approved_for_all(owner, bob));
+
+        assert_eq!(
+            data.permit(owner, bob, true, 1, 0, signature),
+            Err(PSP34Error::NonceUsed)
+        );
+
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        let (signature, _) = sign(7, &data.permit_hash(owner, bob, false, 1, 1));
+        assert_eq!(
+            data.permit(owner, bob, false, 1, 1, signature),
+            Err(PSP34Error::PermitExpired)
+        );
+        assert!(data.is_approved_for_all(owner, bob));
+    }
+
+    #[ink::test]
+    fn is_sold_out_once_the_cap_is_minted() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        assert!(!data.is_sold_out());
+        data.mint(alice, alice).unwrap();
+        assert!(!data.is_sold_out());
+
+        data.set_max_supply(Some(2)).unwrap();
+        assert!(!data.is_sold_out());
+        data.mint(alice, alice).unwrap();
+        assert!(data.is_sold_out());
+
+        data.set_max_supply(None).unwrap();
+        assert!(!data.is_sold_out());
+    }
+
+    #[ink::test]
+    fn missing_owned_token_entry_fails_cleanly() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+        data.owned_tokens.remove((alice, 1));
+
+        assert_eq!(
+            data.transfer(alice, bob, Id::U128(0), vec![]),
+            Err(PSP34Error::InconsistentIndex)
+        );
+        // nothing moved before the inconsistency was found
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+        assert_eq!(data.balance_of(alice), 2);
+    }
+
+    #[cfg(feature = "data-uri")]
+    #[ink::test]
+    fn data_uri_embeds_the_attributes_as_json() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint_with_attributes(
+            alice,
+            alice,
+            vec![
+                (b"name".to_vec(), b"Ape \"1\"".to_vec()),
+                (b"level".to_vec(), b"2".to_vec()),
+            ],
+        )
+        .unwrap();
+
+        // base64 of {"name":"Ape \"1\"","level":"2"}
+        assert_eq!(
+            data.data_uri(Id::U128(0)),
+            Some(
+                b"data:application/json;base64,eyJuYW1lIjoiQXBlIFwiMVwiIiwibGV2ZWwiOiIyIn0="
+                    .to_vec()
+            )
+        );
+        assert_eq!(data.data_uri(Id::U128(1)), None);
+    }
+
+    #[ink::test]
+    fn approvals_are_inactive_once_the_last_token_leaves() {
+        let mut data = PSP34Data::new();
+        let (alice, bob, charlie) = (account(1), account(2), account(3));
+        data.mint(alice, alice).unwrap();
+        data.approve(alice, bob, None, true).unwrap();
+        assert!(data.has_active_approvals(alice));
+
+        data.transfer(alice, charlie, Id::U128(0), vec![]).unwrap();
+        assert!(!data.has_active_approvals(alice));
+        assert_eq!(data.operators_for_all(alice), vec![bob]);
+
+        // they apply again to the tokens received later
+        data.transfer(charlie, alice, Id::U128(0), vec![]).unwrap();
+        assert!(data.has_active_approvals(alice));
+    }
+
+    #[ink::test]
+    fn collection_name_follows_custom_well_known_keys() {
+        let mut data = PSP34Data::new_with_config(PSP34Config {
+            well_known_keys: Some(WellKnownKeys {
+                name: b"metadata.name".to_vec(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        data.set_collection_attribute(b"name".to_vec(), b"Default".to_vec())
+            .unwrap();
+        assert_eq!(data.collection_name(), None);
+
+        data.set_collection_attribute(b"metadata.name".to_vec(), b"Apes".to_vec())
+            .unwrap();
+        assert_eq!(data.collection_name(), Some(b"Apes".to_vec()));
+        assert_eq!(data.well_known_keys().symbol, WellKnownKeys::SYMBOL);
+    }
+
+    #[ink::test]
+    fn allowlist_remaining_reflects_the_allowance_left() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        assert_eq!(data.allowlist_remaining(alice), 0);
+
+        data.set_allowlist_only(true);
+        data.set_allowlist(alice, 3);
+        assert_eq!(data.allowlist_remaining(alice), 3);
+
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, bob).unwrap();
+        assert_eq!(data.allowlist_remaining(alice), 1);
+        assert_eq!(data.allowlist_remaining(bob), 0);
+    }
+
+    #[ink::test]
+    fn rekey_fails_cleanly_on_an_out_of_range_index() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.mint(alice, alice).unwrap();
+        data.all_tokens_index.insert(Id::U128(0), &u128::MAX);
+
+        assert_eq!(
+            data.rekey(Id::U128(0), Id::U8(1)),
+            Err(PSP34Error::InconsistentIndex)
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+        assert_eq!(data.owner_of(Id::U8(1)), None);
+    }
+
+    #[ink::test]
+    fn transfer_unchecked_keeps_every_check_but_the_approval() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.mint(alice, alice).unwrap();
+
+        data.set_transfers_frozen(true);
+        assert_eq!(
+            data.transfer_unchecked(alice, bob, Id::U128(0)),
+            Err(PSP34Error::TransfersFrozen)
+        );
+        data.set_transfers_frozen(false);
+
+        data.set_transfer_unlock_block(Id::U128(0), 10).unwrap();
+        assert_eq!(
+            data.transfer_unchecked(alice, bob, Id::U128(0)),
+            Err(PSP34Error::TokenLocked)
+        );
+        data.set_transfer_unlock_block(Id::U128(0), 0).unwrap();
+
+        data.set_min_balance_to_keep(2);
+        assert_eq!(
+            data.transfer_unchecked(alice, bob, Id::U128(0)),
+            Err(PSP34Error::BelowMinBalance)
+        );
+        data.set_min_balance_to_keep(0);
+
+        data.set_transfer_burn_bps(10_000).unwrap();
+        data.transfer_unchecked(alice, bob, Id::U128(0)).unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+    }
+
+    #[ink::test]
+    fn admin_transfer_claws_back_past_the_transfer_restrictions() {
+        let mut data = PSP34Data::new();
+        let (alice, bob) = (account(1), account(2));
+        data.mint(alice, alice).unwrap();
+        data.set_transfers_frozen(true);
+        data.set_transfer_unlock_block(Id::U128(0), 10).unwrap();
+        data.set_min_balance_to_keep(1);
+        data.set_transfer_burn_bps(10_000).unwrap();
+
+        let events = data.admin_transfer(alice, bob, Id::U128(0)).unwrap();
+        assert_eq!(data.owner_of(Id::U128(0)), Some(bob));
+        assert_eq!(
+            events.last(),
+            Some(&PSP34Event::AdminTransfer {
+                from: alice,
+                to: bob,
+                id: Id::U128(0)
+            })
+        );
+
+        data.mint_soulbound(alice, alice).unwrap();
+        assert_eq!(
+            data.admin_transfer(alice, bob, Id::U128(1)),
+            Err(PSP34Error::Soulbound)
+        );
+    }
+
+    #[ink::test]
+    fn mint_rejects_more_attributes_than_a_batch() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_max_batch_size(2);
+        let attribute = |n: u8| (vec![n], vec![n]);
+
+        assert_eq!(
+            data.mint_with_attributes(alice, alice, (0..3).map(attribute).collect()),
+            Err(PSP34Error::BatchTooLarge)
+        );
+        assert_eq!(
+            data.mint_with_id(alice, Id::U128(7), (0..3).map(attribute).collect()),
+            Err(PSP34Error::BatchTooLarge)
+        );
+
+        // the defaults count towards the limit unless overridden
+        data.set_default_attributes(vec![attribute(0)]).unwrap();
+        assert_eq!(
+            data.mint_with_attributes(alice, alice, vec![attribute(1), attribute(2)]),
+            Err(PSP34Error::BatchTooLarge)
+        );
+        assert_eq!(data.total_supply(), 0);
+        data.mint_with_attributes(alice, alice, vec![attribute(0), attribute(1)])
+            .unwrap();
+        assert_eq!(data.attribute_count(Id::U128(0)), 2);
+    }
+
+    #[ink::test]
+    fn attributes_are_paged_past_the_batch_bound() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        let attributes: Vec<_> = (0..5u8).map(|n| (vec![n], vec![n])).collect();
+        data.mint_with_attributes(alice, alice, attributes.clone())
+            .unwrap();
+        data.set_max_batch_size(2);
+
+        assert_eq!(data.all_attributes(Id::U128(0)), attributes[..2].to_vec());
+        assert_eq!(data.attribute_count(Id::U128(0)), 5);
+        assert_eq!(data.token_info(Id::U128(0)).unwrap().attribute_count, 5);
+
+        let mut paged = Vec::new();
+        while paged.len() < data.attribute_count(Id::U128(0)) as usize {
+            paged.extend(data.attributes_from(Id::U128(0), paged.len() as u32));
+        }
+        assert_eq!(paged, attributes);
+        assert!(data.attributes_from(Id::U128(0), 5).is_empty());
+    }
+
+    #[ink::test]
+    fn next_token_id_probes_a_bounded_run_of_taken_ids() {
+        let mut data = PSP34Data::new();
+        let alice = account(1);
+        data.set_max_batch_size(3);
+
+        for n in 0..4 {
+            data.mint_with_id(alice, Id::U128(n), vec![]).unwrap();
+        }
+        assert_eq!(data.next_token_id(), Err(PSP34Error::NoFreeId));
+        assert_eq!(data.mint(alice, alice), Err(PSP34Error::NoFreeId));
+        assert_eq!(data.total_supply(), 4);
+
+        // freeing an id of the run, or probing further, unblocks the mint
+        data.set_max_batch_size(4);
+        assert_eq!(data.next_token_id(), Ok(Id::U128(4)));
+        data.set_max_batch_size(3);
+        data.burn(alice, Id::U128(2)).unwrap();
+        data.mint(alice, alice).unwrap();
+        assert_eq!(data.owner_of(Id::U128(2)), Some(alice));
     }
 }
@@ -1,4 +1,7 @@
 use ink::prelude::string::String;
+use ink::LangError;
+
+use crate::PSP34Result;
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -21,4 +24,209 @@ pub enum PSP34Error {
     OutOfBoundsIndex,
     /// Returned if trying to call approve when operator has all approved
     NotAllowedToApprove,
+    /// Returned if `from` isn't the owner of the token being transferred
+    IncorrectOwner,
+    /// Returned if a signature doesn't match the expected signer
+    InvalidSignature,
+    /// Returned if an id can't identify a token, e.g. an empty `Id::Bytes`
+    InvalidId,
+    /// Returned if a batch operation is given more items than allowed
+    BatchTooLarge,
+    /// Returned if the value transferred doesn't cover the price
+    InsufficientPayment,
+    /// Returned if minting outside of the mint window
+    MintNotActive,
+    /// Returned if minting during the allowlist phase without allowance
+    NotAllowlisted,
+    /// Returned if transferring a token before its unlock block
+    TokenLocked,
+    /// Returned if transferring a soulbound token
+    Soulbound,
+    /// Returned if the receiving account would hold more tokens than allowed
+    BalanceCapExceeded,
+    /// Returned if an attribute key or value is longer than allowed
+    AttributeTooLong,
+    /// Returned if an account mints again before its cooldown elapsed
+    MintCooldown,
+    /// Returned if changing the attributes of a frozen token
+    MetadataFrozen,
+    /// Returned if the caller is neither the owner of a token nor approved
+    NotOwner,
+    /// Returned if a transfer leaves the sender with fewer tokens than required
+    BelowMinBalance,
+    /// Returned if a signed permit's nonce was already used
+    NonceUsed,
+    /// Returned if a signed permit is submitted after its deadline
+    PermitExpired,
+    /// Returned if transferring while transfers are frozen collection-wide
+    TransfersFrozen,
+    /// Returned if transferring with an attribute while transfers may burn
+    TransferMayBurn,
+    /// Returned if the token indexes disagree with each other, which
+    /// denotes a bug rather than a misuse
+    InconsistentIndex,
+    /// Returned if a signed mint voucher was already redeemed
+    VoucherUsed,
+    /// Returned if the ids a mint would assign next are all taken, e.g.
+    /// by `mint_with_id`
+    NoFreeId,
+}
+
+impl PSP34Error {
+    /// Returns a stable, human readable description of the error.
+    ///
+    /// Variants carrying a message describe their kind only, use
+    /// `Display` to include the message as well.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PSP34Error::Custom(_) => "custom error",
+            PSP34Error::SelfApprove => "owner can't approve self",
+            PSP34Error::NotApproved => "caller is not approved",
+            PSP34Error::TokenExists => "token already exists",
+            PSP34Error::TokenNotExists => "token doesn't exist",
+            PSP34Error::ReachedMaxSupply => "reached max supply",
+            PSP34Error::SafeTransferCheckFailed(_) => "safe transfer check failed",
+            PSP34Error::OutOfBoundsIndex => "index out of bounds",
+            PSP34Error::NotAllowedToApprove => "operator is already approved for all tokens",
+            PSP34Error::IncorrectOwner => "'from' is not the owner of the token",
+            PSP34Error::InvalidSignature => "invalid signature",
+            PSP34Error::InvalidId => "invalid token id",
+            PSP34Error::BatchTooLarge => "batch too large",
+            PSP34Error::InsufficientPayment => "insufficient payment",
+            PSP34Error::MintNotActive => "mint is not active",
+            PSP34Error::NotAllowlisted => "account is not allowlisted",
+            PSP34Error::TokenLocked => "token is locked",
+            PSP34Error::Soulbound => "token is soulbound",
+            PSP34Error::BalanceCapExceeded => "balance cap exceeded",
+            PSP34Error::AttributeTooLong => "attribute too long",
+            PSP34Error::MintCooldown => "mint cooldown not elapsed",
+            PSP34Error::MetadataFrozen => "metadata is frozen",
+            PSP34Error::NotOwner => "caller is not the owner",
+            PSP34Error::BelowMinBalance => "balance below minimum",
+            PSP34Error::NonceUsed => "nonce already used",
+            PSP34Error::PermitExpired => "permit expired",
+            PSP34Error::TransfersFrozen => "transfers are frozen",
+            PSP34Error::TransferMayBurn => "transfers may burn the token",
+            PSP34Error::InconsistentIndex => "inconsistent token index",
+            PSP34Error::VoucherUsed => "voucher already used",
+            PSP34Error::NoFreeId => "no free id to mint",
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for PSP34Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PSP34Error::Custom(message) => write!(f, "{}", message),
+            PSP34Error::SafeTransferCheckFailed(message) => {
+                write!(f, "{}: {}", self.as_str(), message)
+            }
+            _ => write!(f, "{}", self.as_str()),
+        }
+    }
+}
+
+impl From<&str> for PSP34Error {
+    fn from(message: &str) -> Self {
+        PSP34Error::Custom(message.into())
+    }
+}
+
+/// Error of a cross-contract call to a PSP34 message, telling apart the
+/// errors returned by the collection from the ones raised by ink! while
+/// dispatching the call.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP34CallError {
+    /// The message was executed and returned an error
+    PSP34(PSP34Error),
+    /// The message couldn't be dispatched, e.g. an unknown selector
+    Lang(LangError),
+    /// The call didn't complete, e.g. the callee trapped or isn't a contract
+    CallFailed,
+}
+
+impl From<PSP34Error> for PSP34CallError {
+    fn from(error: PSP34Error) -> Self {
+        PSP34CallError::PSP34(error)
+    }
+}
+
+impl From<LangError> for PSP34CallError {
+    fn from(error: LangError) -> Self {
+        PSP34CallError::Lang(error)
+    }
+}
+
+impl From<ink::env::Error> for PSP34CallError {
+    fn from(_: ink::env::Error) -> Self {
+        PSP34CallError::CallFailed
+    }
+}
+
+/// Collapses the nested result of a cross-contract call to a PSP34
+/// message, as returned by ink!'s generated contract refs `try_*`
+/// methods, into a single `Result`.
+pub fn flatten_psp34_result<T>(
+    result: Result<PSP34Result<T>, LangError>,
+) -> Result<T, PSP34CallError> {
+    Ok(result??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_errors_are_told_apart() {
+        assert_eq!(flatten_psp34_result(Ok(Ok(1))), Ok(1));
+        assert_eq!(
+            flatten_psp34_result::<()>(Ok(Err(PSP34Error::NotApproved))),
+            Err(PSP34CallError::PSP34(PSP34Error::NotApproved))
+        );
+        assert_eq!(
+            flatten_psp34_result::<()>(Err(LangError::CouldNotReadInput)),
+            Err(PSP34CallError::Lang(LangError::CouldNotReadInput))
+        );
+        assert_eq!(
+            PSP34CallError::from(ink::env::Error::CalleeTrapped),
+            PSP34CallError::CallFailed
+        );
+    }
+
+    #[test]
+    fn errors_format_to_stable_strings() {
+        assert_eq!(
+            PSP34Error::NotApproved.to_string(),
+            "caller is not approved"
+        );
+        assert_eq!(
+            PSP34Error::TokenNotExists.to_string(),
+            PSP34Error::TokenNotExists.as_str()
+        );
+        assert_eq!(PSP34Error::from("sold out").to_string(), "sold out");
+        assert_eq!(
+            PSP34Error::SafeTransferCheckFailed("rejected".into()).to_string(),
+            "safe transfer check failed: rejected"
+        );
+    }
+
+    #[test]
+    fn both_error_layers_convert_with_the_question_mark() {
+        fn call(result: Result<PSP34Result<u32>, LangError>) -> Result<u32, PSP34CallError> {
+            let inner = result?;
+            Ok(inner?)
+        }
+
+        assert_eq!(call(Ok(Ok(7))), Ok(7));
+        assert_eq!(
+            call(Ok(Err(PSP34Error::TokenNotExists))),
+            Err(PSP34CallError::PSP34(PSP34Error::TokenNotExists))
+        );
+        assert_eq!(
+            call(Err(LangError::CouldNotReadInput)),
+            Err(PSP34CallError::Lang(LangError::CouldNotReadInput))
+        );
+    }
 }
@@ -1,12 +1,13 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-#[cfg(feature = "contract")]
 #[ink::contract]
-mod token {
-    use ink::prelude::vec::Vec;
+pub mod token {
+    use ink::prelude::{string::String, vec::Vec};
+    use psp34::interfaces::*;
+    use psp34::types::ApprovalStatus;
     use psp34::{
-        Id, PSP34Burnable, PSP34Data, PSP34Enumerable, PSP34Error, PSP34Event, PSP34Metadata,
-        PSP34Mintable, PSP34,
+        Id, PSP34Burnable, PSP34Data, PSP34Enumerable, PSP34Error, PSP34Event, PSP34Interop,
+        PSP34Introspection, PSP34Metadata, PSP34Mintable, PSP34,
     };
 
     #[ink(storage)]
@@ -14,6 +15,18 @@ mod token {
         data: PSP34Data,
     }
 
+    /// Interfaces reported by `supports_interface`, keep in sync with the
+    /// traits implemented below.
+    const SUPPORTED_INTERFACES: &[u32] = &[
+        PSP34_INTERFACE_ID,
+        PSP34_INTEROP_INTERFACE_ID,
+        PSP34_MINTABLE_INTERFACE_ID,
+        PSP34_BURNABLE_INTERFACE_ID,
+        PSP34_METADATA_INTERFACE_ID,
+        PSP34_ENUMERABLE_INTERFACE_ID,
+        PSP34_INTROSPECTION_INTERFACE_ID,
+    ];
+
     impl Token {
         #[ink(constructor)]
         pub fn new() -> Self {
@@ -25,9 +38,18 @@ mod token {
         fn emit_events(&self, events: Vec<PSP34Event>) {
             for event in events {
                 match event {
-                    PSP34Event::Transfer { from, to, id } => {
-                        self.env().emit_event(Transfer { from, to, id })
-                    }
+                    PSP34Event::Transfer {
+                        from,
+                        to,
+                        id,
+                        operator,
+                    } => self.env().emit_event(Transfer {
+                        from,
+                        to,
+                        id,
+                        operator,
+                    }),
+                    PSP34Event::Burn { from, id } => self.env().emit_event(Burn { from, id }),
                     PSP34Event::Approval {
                         owner,
                         operator,
@@ -42,6 +64,39 @@ mod token {
                     PSP34Event::AttributeSet { id, key, data } => {
                         self.env().emit_event(AttributeSet { id, key, data })
                     }
+                    PSP34Event::MintPriceChanged { old, new } => {
+                        self.env().emit_event(MintPriceChanged { old, new })
+                    }
+                    PSP34Event::SoldOut { total_supply } => {
+                        self.env().emit_event(SoldOut { total_supply })
+                    }
+                    PSP34Event::Revealed { revealed_uri } => {
+                        self.env().emit_event(Revealed { revealed_uri })
+                    }
+                    PSP34Event::AdminTransfer { from, to, id } => {
+                        self.env().emit_event(AdminTransfer { from, to, id })
+                    }
+                    PSP34Event::Rekeyed { old_id, new_id } => {
+                        self.env().emit_event(Rekeyed { old_id, new_id })
+                    }
+                    PSP34Event::DelegateChanged {
+                        delegator,
+                        from_delegate,
+                        to_delegate,
+                    } => self.env().emit_event(DelegateChanged {
+                        delegator,
+                        from_delegate,
+                        to_delegate,
+                    }),
+                    PSP34Event::DelegateVotesChanged {
+                        delegate,
+                        previous_votes,
+                        new_votes,
+                    } => self.env().emit_event(DelegateVotesChanged {
+                        delegate,
+                        previous_votes,
+                        new_votes,
+                    }),
                 }
             }
         }
@@ -60,15 +115,71 @@ mod token {
         from: Option<AccountId>,
         to: Option<AccountId>,
         id: Id,
+        #[ink(topic)]
+        operator: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        id: Id,
     }
 
     #[ink(event)]
     pub struct AttributeSet {
+        #[ink(topic)]
         id: Id,
+        #[ink(topic)]
         key: Vec<u8>,
         data: Vec<u8>,
     }
 
+    #[ink(event)]
+    pub struct MintPriceChanged {
+        old: Balance,
+        new: Balance,
+    }
+
+    #[ink(event)]
+    pub struct SoldOut {
+        total_supply: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Revealed {
+        revealed_uri: String,
+    }
+
+    #[ink(event)]
+    pub struct AdminTransfer {
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+    }
+
+    #[ink(event)]
+    pub struct Rekeyed {
+        #[ink(topic)]
+        old_id: Id,
+        #[ink(topic)]
+        new_id: Id,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        delegator: AccountId,
+        from_delegate: Option<AccountId>,
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        delegate: AccountId,
+        previous_votes: u128,
+        new_votes: u128,
+    }
+
     impl PSP34 for Token {
         #[ink(message)]
         fn collection_id(&self) -> Id {
@@ -87,11 +198,31 @@ mod token {
             self.data.owner_of(id)
         }
 
+        #[ink(message)]
+        fn owner_of_or_zero(&self, id: Id) -> AccountId {
+            self.data.owner_of_or_zero(id)
+        }
+
+        #[ink(message)]
+        fn owners_of(&self, ids: Vec<Id>) -> Result<Vec<Option<AccountId>>, PSP34Error> {
+            self.data.owners_of(ids)
+        }
+
         #[ink(message)]
         fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
             self.data.allowance(owner, operator, id)
         }
 
+        #[ink(message)]
+        fn allowance_batch(
+            &self,
+            owner: AccountId,
+            operator: AccountId,
+            ids: Vec<Id>,
+        ) -> Result<Vec<bool>, PSP34Error> {
+            self.data.allowance_batch(owner, operator, ids)
+        }
+
         #[ink(message)]
         fn approve(
             &mut self,
@@ -121,7 +252,9 @@ mod token {
             id: Id,
             data: Vec<u8>,
         ) -> Result<(), PSP34Error> {
-            let events = self.data.transfer_from(from, to, id, data)?;
+            let events = self
+                .data
+                .transfer_from(self.env().caller(), from, to, id, data)?;
             self.emit_events(events);
             Ok(())
         }
@@ -132,12 +265,50 @@ mod token {
         }
     }
 
+    impl PSP34Interop for Token {
+        #[ink(message)]
+        fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.data.is_approved_for_all(owner, operator)
+        }
+
+        #[ink(message)]
+        fn get_approved(&self, id: Id) -> Option<AccountId> {
+            self.data.get_approved(id)
+        }
+
+        #[ink(message)]
+        fn approval_status(&self, owner: AccountId, operator: AccountId) -> ApprovalStatus {
+            self.data.approval_status(owner, operator)
+        }
+
+        #[ink(message)]
+        fn approve_token(
+            &mut self,
+            operator: AccountId,
+            id: Id,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            PSP34::approve(self, operator, Some(id), approved)
+        }
+
+        #[ink(message)]
+        fn approve_all(&mut self, operator: AccountId, approved: bool) -> Result<(), PSP34Error> {
+            PSP34::approve(self, operator, None, approved)
+        }
+
+        #[ink(message)]
+        fn operators_for_token(&self, owner: AccountId, id: Id) -> Vec<AccountId> {
+            self.data.operators_for_token(owner, id)
+        }
+    }
+
     impl PSP34Mintable for Token {
         #[ink(message)]
-        fn mint(&mut self, account: AccountId) -> Result<(), PSP34Error> {
-            let events = self.data.mint(account)?;
+        fn mint(&mut self, account: AccountId) -> Result<Id, PSP34Error> {
+            let id = self.data.next_token_id()?;
+            let events = self.data.mint(self.env().caller(), account)?;
             self.emit_events(events);
-            Ok(())
+            Ok(id)
         }
 
         #[ink(message)]
@@ -145,17 +316,33 @@ mod token {
             &mut self,
             account: AccountId,
             attributes: Vec<(Vec<u8>, Vec<u8>)>,
-        ) -> Result<(), PSP34Error> {
-            let events = self.data.mint_with_attributes(account, attributes)?;
+        ) -> Result<Id, PSP34Error> {
+            let id = self.data.next_token_id()?;
+            let events =
+                self.data
+                    .mint_with_attributes(self.env().caller(), account, attributes)?;
             self.emit_events(events);
-            Ok(())
+            Ok(id)
+        }
+
+        #[ink(message)]
+        fn mint_with_uri(&mut self, account: AccountId, uri: Vec<u8>) -> Result<Id, PSP34Error> {
+            let id = self.data.next_token_id()?;
+            let events = self.data.mint_with_uri(self.env().caller(), account, uri)?;
+            self.emit_events(events);
+            Ok(id)
+        }
+
+        #[ink(message)]
+        fn next_token_id(&self) -> Result<Id, PSP34Error> {
+            self.data.next_token_id()
         }
     }
 
     impl PSP34Burnable for Token {
         #[ink(message)]
         fn burn(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error> {
-            let events = self.data.burn(account, id)?;
+            let events = self.data.burn_from(self.env().caller(), account, id)?;
             self.emit_events(events);
             Ok(())
         }
@@ -166,6 +353,30 @@ mod token {
         fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
             self.data.get_attribute(id, key)
         }
+
+        #[ink(message)]
+        fn try_get_attribute(&self, id: Id, key: Vec<u8>) -> Result<Option<Vec<u8>>, PSP34Error> {
+            self.data.try_get_attribute(id, key)
+        }
+
+        #[ink(message)]
+        fn get_attribute_full(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
+            self.data.get_attribute_full(id, key)
+        }
+
+        #[ink(message)]
+        fn all_attributes(&self, id: Id) -> Vec<(Vec<u8>, Vec<u8>)> {
+            self.data.all_attributes(id)
+        }
+
+        #[ink(message)]
+        fn get_attribute_for_tokens(
+            &self,
+            ids: Vec<Id>,
+            key: Vec<u8>,
+        ) -> Result<Vec<Option<Vec<u8>>>, PSP34Error> {
+            self.data.get_attribute_for_tokens(ids, key)
+        }
     }
 
     impl PSP34Enumerable for Token {
@@ -174,9 +385,31 @@ mod token {
             self.data.token_by_index(index)
         }
 
+        #[ink(message)]
+        fn next_existing_token(&self, from_index: u128) -> Option<(u128, Id)> {
+            self.data.next_existing_token(from_index)
+        }
+
         #[ink(message)]
         fn owners_token_by_index(&self, owner: AccountId, index: u128) -> Option<Id> {
             self.data.owners_token_by_index(owner, index)
         }
+
+        #[ink(message)]
+        fn index_of_token(&self, id: Id) -> Option<u128> {
+            self.data.index_of_token(id)
+        }
+
+        #[ink(message)]
+        fn index_of_owner_token(&self, owner: AccountId, id: Id) -> Option<u128> {
+            self.data.index_of_owner_token(owner, id)
+        }
+    }
+
+    impl PSP34Introspection for Token {
+        #[ink(message)]
+        fn supports_interface(&self, interface_id: u32) -> bool {
+            SUPPORTED_INTERFACES.contains(&interface_id)
+        }
     }
 }
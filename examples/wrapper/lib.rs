@@ -0,0 +1,329 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod wrapper {
+    use ink::prelude::vec::Vec;
+    use psp34::{
+        Id, PSP34Data, PSP34Error, PSP34Event, PSP34Receiver, PSP34Ref, PSP34Wrapper,
+        PSP34WrapperData, PSP34,
+    };
+
+    #[ink(storage)]
+    pub struct Wrapper {
+        data: PSP34Data,
+        wrapper: PSP34WrapperData,
+    }
+
+    impl Wrapper {
+        #[ink(constructor)]
+        pub fn new(underlying: AccountId) -> Self {
+            Self {
+                data: PSP34Data::new(),
+                wrapper: PSP34WrapperData::new(underlying),
+            }
+        }
+
+        fn emit_events(&self, events: Vec<PSP34Event>) {
+            for event in events {
+                match event {
+                    PSP34Event::Transfer {
+                        from,
+                        to,
+                        id,
+                        operator,
+                    } => self.env().emit_event(Transfer {
+                        from,
+                        to,
+                        id,
+                        operator,
+                    }),
+                    PSP34Event::Burn { from, id } => self.env().emit_event(Burn { from, id }),
+                    PSP34Event::Approval {
+                        owner,
+                        operator,
+                        id,
+                        approved,
+                    } => self.env().emit_event(Approval {
+                        owner,
+                        operator,
+                        id,
+                        approved,
+                    }),
+                    // The wrapper only mints, burns, approves and transfers
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        owner: AccountId,
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        id: Id,
+        #[ink(topic)]
+        operator: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        id: Id,
+    }
+
+    impl PSP34 for Wrapper {
+        #[ink(message)]
+        fn collection_id(&self) -> Id {
+            self.data.collection_id()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u32 {
+            self.data.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn owner_of(&self, id: Id) -> Option<AccountId> {
+            self.data.owner_of(id)
+        }
+
+        #[ink(message)]
+        fn owner_of_or_zero(&self, id: Id) -> AccountId {
+            self.data.owner_of_or_zero(id)
+        }
+
+        #[ink(message)]
+        fn owners_of(&self, ids: Vec<Id>) -> Result<Vec<Option<AccountId>>, PSP34Error> {
+            self.data.owners_of(ids)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
+            self.data.allowance(owner, operator, id)
+        }
+
+        #[ink(message)]
+        fn allowance_batch(
+            &self,
+            owner: AccountId,
+            operator: AccountId,
+            ids: Vec<Id>,
+        ) -> Result<Vec<bool>, PSP34Error> {
+            self.data.allowance_batch(owner, operator, ids)
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            let events = self
+                .data
+                .approve(self.env().caller(), operator, id, approved)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error> {
+            let events = self.data.transfer(self.env().caller(), to, id, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: Id,
+            data: Vec<u8>,
+        ) -> Result<(), PSP34Error> {
+            let events = self
+                .data
+                .transfer_from(self.env().caller(), from, to, id, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.data.total_supply()
+        }
+    }
+
+    impl PSP34Wrapper for Wrapper {
+        #[ink(message)]
+        fn underlying(&self) -> AccountId {
+            self.wrapper.underlying()
+        }
+
+        #[ink(message)]
+        fn deposit_for(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error> {
+            let contract = self.env().account_id();
+            let mut underlying = PSP34Ref::from(self.wrapper.underlying());
+            let events = self.wrapper.deposit_for_with(
+                &mut underlying,
+                &mut self.data,
+                contract,
+                account,
+                id,
+            )?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn withdraw_to(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            let mut underlying = PSP34Ref::from(self.wrapper.underlying());
+            let events = self.wrapper.withdraw_to_with(
+                &mut underlying,
+                &mut self.data,
+                caller,
+                account,
+                id,
+            )?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34Receiver for Wrapper {
+        /// Accepts the underlying tokens pulled by `deposit_for` only, as
+        /// tokens sent any other way would have no wrapped counterpart.
+        #[ink(message)]
+        fn on_received(
+            &mut self,
+            operator: AccountId,
+            _from: AccountId,
+            _id: Id,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP34Error> {
+            if self.env().caller() != self.wrapper.underlying()
+                || operator != self.env().account_id()
+            {
+                return Err(PSP34Error::NotApproved);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+        use psp34::PSP34Mintable;
+        use psp34_example::token::TokenRef;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(additional_contracts = "../Cargo.toml")]
+        async fn deposit_and_withdraw_go_through_psp34_ref(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let token = client
+                .instantiate("psp34_example", &ink_e2e::alice(), TokenRef::new(), 0, None)
+                .await
+                .expect("token instantiate failed")
+                .account_id;
+            let wrapper = client
+                .instantiate(
+                    "psp34_wrapper_example",
+                    &ink_e2e::alice(),
+                    WrapperRef::new(token.clone()),
+                    0,
+                    None,
+                )
+                .await
+                .expect("wrapper instantiate failed")
+                .account_id;
+
+            let mint = build_message::<TokenRef>(token.clone()).call(|token| token.mint(alice));
+            let id = client
+                .call(&ink_e2e::alice(), mint, 0, None)
+                .await
+                .expect("mint failed")
+                .return_value()?;
+            let approve = build_message::<TokenRef>(token.clone())
+                .call(|token| token.approve(wrapper.clone(), Some(id.clone()), true));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            let deposit = build_message::<WrapperRef>(wrapper.clone())
+                .call(|wrapper| wrapper.deposit_for(alice, id.clone()));
+            client
+                .call(&ink_e2e::alice(), deposit, 0, None)
+                .await
+                .expect("deposit failed");
+            let owner_of =
+                build_message::<TokenRef>(token.clone()).call(|token| token.owner_of(id.clone()));
+            let owner = client
+                .call_dry_run(&ink_e2e::alice(), &owner_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(owner, Some(wrapper.clone()));
+
+            let withdraw = build_message::<WrapperRef>(wrapper.clone())
+                .call(|wrapper| wrapper.withdraw_to(alice, id.clone()));
+            client
+                .call(&ink_e2e::alice(), withdraw, 0, None)
+                .await
+                .expect("withdraw failed");
+            let owner = client
+                .call_dry_run(&ink_e2e::alice(), &owner_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(owner, Some(alice));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../Cargo.toml")]
+        async fn failing_underlying_call_is_returned_as_an_error(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            // Bob's account holds no contract, so every call to it fails
+            let wrapper = client
+                .instantiate(
+                    "psp34_wrapper_example",
+                    &ink_e2e::alice(),
+                    WrapperRef::new(bob),
+                    0,
+                    None,
+                )
+                .await
+                .expect("wrapper instantiate failed")
+                .account_id;
+
+            let deposit = build_message::<WrapperRef>(wrapper.clone())
+                .call(|wrapper| wrapper.deposit_for(alice, Id::U128(1)));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &deposit, 0, None)
+                .await
+                .return_value();
+            assert_eq!(
+                result,
+                Err(PSP34Error::Custom("underlying call failed".into()))
+            );
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use ink::primitives::AccountId;
+
+use crate::{Id, PSP34Result};
+
+/// Custom logic run around `PSP34Data::mint_with_hooks`, e.g. allowlist
+/// checks or payment capture, without re-implementing the mint itself.
+///
+/// Both hooks default to no-ops, so implementers only override the ones
+/// they need. Returning an error from either hook aborts the mint; since
+/// `after_mint` runs once storage was already written, the contract must
+/// propagate the error from its message for ink! to revert the changes.
+pub trait PSP34MintHooks {
+    /// Called before anything is written, with the `id` about to be minted.
+    fn before_mint(&mut self, _account: AccountId, _id: &Id) -> PSP34Result {
+        Ok(())
+    }
+
+    /// Called once the `id` token was minted to `account`.
+    fn after_mint(&mut self, _account: AccountId, _id: &Id) -> PSP34Result {
+        Ok(())
+    }
+}
+
+/// No hooks at all, used by the plain mint methods.
+impl PSP34MintHooks for () {}
@@ -0,0 +1,24 @@
+//! Interface ids for `supports_interface`, in the spirit of ERC-165.
+//!
+//! Each id is the ink! selector id of the trait's name, so it stays the
+//! same when messages are added to an extension.
+
+use ink::selector_id;
+
+pub const PSP34_INTERFACE_ID: u32 = selector_id!("PSP34");
+pub const PSP34_MINTABLE_INTERFACE_ID: u32 = selector_id!("PSP34Mintable");
+pub const PSP34_BURNABLE_INTERFACE_ID: u32 = selector_id!("PSP34Burnable");
+pub const PSP34_ENUMERABLE_INTERFACE_ID: u32 = selector_id!("PSP34Enumerable");
+pub const PSP34_METADATA_INTERFACE_ID: u32 = selector_id!("PSP34Metadata");
+pub const PSP34_MUTABLE_METADATA_INTERFACE_ID: u32 = selector_id!("PSP34MutableMetadata");
+pub const PSP34_METADATA_HASH_INTERFACE_ID: u32 = selector_id!("PSP34MetadataHash");
+pub const PSP34_INTEROP_INTERFACE_ID: u32 = selector_id!("PSP34Interop");
+pub const PSP34_CAPPED_INTERFACE_ID: u32 = selector_id!("PSP34Capped");
+pub const PSP34_SNAPSHOT_INTERFACE_ID: u32 = selector_id!("PSP34Snapshot");
+pub const PSP34_VOTES_INTERFACE_ID: u32 = selector_id!("PSP34Votes");
+pub const PSP34_SWAP_INTERFACE_ID: u32 = selector_id!("PSP34Swap");
+pub const PSP34_TRANSFER_WITH_ATTRIBUTE_INTERFACE_ID: u32 =
+    selector_id!("PSP34TransferWithAttribute");
+pub const PSP34_WRAPPER_INTERFACE_ID: u32 = selector_id!("PSP34Wrapper");
+pub const PSP34_RECEIVER_INTERFACE_ID: u32 = selector_id!("PSP34Receiver");
+pub const PSP34_INTROSPECTION_INTERFACE_ID: u32 = selector_id!("PSP34Introspection");
@@ -0,0 +1,42 @@
+//! Keys of the attributes with a conventional meaning.
+//!
+//! Ecosystems disagree on these keys, e.g. `name` or `metadata.name`, so
+//! the metadata helpers of `PSP34Data` read them from its
+//! `well_known_keys`, which a contract can re-map at construction.
+
+use ink::prelude::vec::Vec;
+
+/// Keys the metadata helpers use for the well-known attributes
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct WellKnownKeys {
+    /// Key of the collection name attribute
+    pub name: Vec<u8>,
+    /// Key of the collection symbol attribute
+    pub symbol: Vec<u8>,
+    /// Key of the collection base URI attribute
+    pub base_uri: Vec<u8>,
+    /// Key of the explicit token URI attribute, see `mint_with_uri`
+    pub uri: Vec<u8>,
+}
+
+impl WellKnownKeys {
+    pub const NAME: &'static [u8] = b"name";
+    pub const SYMBOL: &'static [u8] = b"symbol";
+    pub const BASE_URI: &'static [u8] = b"baseUri";
+    pub const URI: &'static [u8] = b"uri";
+}
+
+impl Default for WellKnownKeys {
+    fn default() -> Self {
+        WellKnownKeys {
+            name: WellKnownKeys::NAME.to_vec(),
+            symbol: WellKnownKeys::SYMBOL.to_vec(),
+            base_uri: WellKnownKeys::BASE_URI.to_vec(),
+            uri: WellKnownKeys::URI.to_vec(),
+        }
+    }
+}
@@ -1,43 +1,859 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+#[cfg(feature = "client")]
+mod client;
 mod data;
 mod errors;
+mod hooks;
+pub mod interfaces;
+mod keys;
 mod traits;
 pub mod types;
+mod wrapper;
 
 pub use crate::types::Id;
-pub use data::{PSP34Data, PSP34Event};
-pub use errors::PSP34Error;
-pub use traits::{PSP34Burnable, PSP34Enumerable, PSP34Metadata, PSP34Mintable, PSP34};
+#[cfg(feature = "client")]
+pub use client::PSP34Ref;
+pub use data::{
+    PSP34Data, PSP34Event, DEFAULT_MAX_ATTRIBUTE_KEY_LEN, DEFAULT_MAX_ATTRIBUTE_VALUE_LEN,
+    DEFAULT_MAX_BATCH_SIZE, STORAGE_VERSION,
+};
+pub use errors::{flatten_psp34_result, PSP34CallError, PSP34Error};
+
+/// Result of the fallible PSP34 operations
+pub type PSP34Result<T = ()> = Result<T, PSP34Error>;
+pub use hooks::PSP34MintHooks;
+pub use keys::WellKnownKeys;
+pub use traits::{
+    PSP34Burnable, PSP34Capped, PSP34Enumerable, PSP34Interop, PSP34Introspection, PSP34Metadata,
+    PSP34MetadataHash, PSP34Mintable, PSP34MutableMetadata, PSP34Receiver, PSP34Snapshot,
+    PSP34Swap, PSP34TransferWithAttribute, PSP34Votes, PSP34Wrapper, PSP34,
+};
+pub use wrapper::{PSP34Underlying, PSP34WrapperData};
 
 #[cfg(feature = "contract")]
 #[ink::contract]
 mod token {
+    use crate::interfaces::*;
+    use crate::types::{ApprovalStatus, PSP34Config, TokenInfo};
     use crate::{
-        Id, PSP34Burnable, PSP34Data, PSP34Enumerable, PSP34Error, PSP34Event, PSP34Metadata,
-        PSP34Mintable, PSP34,
+        Id, PSP34Burnable, PSP34Capped, PSP34Data, PSP34Enumerable, PSP34Error, PSP34Event,
+        PSP34Interop, PSP34Introspection, PSP34Metadata, PSP34MetadataHash, PSP34Mintable,
+        PSP34MutableMetadata, PSP34Result, PSP34Snapshot, PSP34Swap, PSP34TransferWithAttribute,
+        PSP34Votes, PSP34,
+    };
+    use ink::env::{
+        call::{build_call, ExecutionInput, Selector},
+        DefaultEnvironment,
     };
-    use ink::prelude::vec::Vec;
+    use ink::prelude::{boxed::Box, string::String, vec, vec::Vec};
+    use ink::selector_bytes;
 
     #[ink(storage)]
     pub struct Token {
         data: PSP34Data,
+        owner: AccountId,
     }
 
+    /// Interfaces reported by `supports_interface`, keep in sync with the
+    /// traits implemented below.
+    const SUPPORTED_INTERFACES: &[u32] = &[
+        PSP34_INTERFACE_ID,
+        PSP34_INTEROP_INTERFACE_ID,
+        PSP34_MINTABLE_INTERFACE_ID,
+        PSP34_CAPPED_INTERFACE_ID,
+        PSP34_BURNABLE_INTERFACE_ID,
+        PSP34_SWAP_INTERFACE_ID,
+        PSP34_VOTES_INTERFACE_ID,
+        PSP34_TRANSFER_WITH_ATTRIBUTE_INTERFACE_ID,
+        PSP34_METADATA_INTERFACE_ID,
+        PSP34_METADATA_HASH_INTERFACE_ID,
+        PSP34_SNAPSHOT_INTERFACE_ID,
+        PSP34_MUTABLE_METADATA_INTERFACE_ID,
+        PSP34_ENUMERABLE_INTERFACE_ID,
+        PSP34_INTROSPECTION_INTERFACE_ID,
+    ];
+
     impl Token {
         #[ink(constructor)]
         pub fn new() -> Self {
             Self {
                 data: PSP34Data::new(),
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Instantiates the contract from `config`, boxed as it dwarfs the
+        /// arguments of the other constructors.
+        #[ink(constructor)]
+        pub fn new_with_config(config: Box<PSP34Config>) -> Self {
+            Self {
+                owner: config.owner.unwrap_or(Self::env().caller()),
+                data: PSP34Data::new_with_config(*config),
+            }
+        }
+
+        fn ensure_owner(&self) -> PSP34Result {
+            if self.env().caller() != self.owner {
+                return Err(PSP34Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        /// Returns the amount of distinct accounts holding at least one token.
+        #[ink(message)]
+        pub fn holder_count(&self) -> u32 {
+            self.data.holder_count()
+        }
+
+        /// Transfers the `id` token from `from` to `to` skipping the
+        /// allowance checks. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn transfer_unchecked(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: Id,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.transfer_unchecked(from, to, id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Sets the maximum amount of tokens that can exist, `None` meaning
+        /// unlimited. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_max_supply(&mut self, max_supply: Option<Balance>) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_max_supply(max_supply)
+        }
+
+        /// Returns the amount of tokens ever minted, unlike `total_supply`
+        /// which burns decrease.
+        #[ink(message)]
+        pub fn lifetime_minted(&self) -> u128 {
+            self.data.lifetime_minted()
+        }
+
+        #[ink(message)]
+        pub fn reserved_supply(&self) -> Balance {
+            self.data.reserved_supply()
+        }
+
+        /// Sets aside `reserved_supply` tokens that only `reserve_mint` can
+        /// mint. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_reserved_supply(&mut self, reserved_supply: Balance) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_reserved_supply(reserved_supply)
+        }
+
+        /// Mints `count` reserved tokens to `to`. Only callable by the owner
+        /// of the contract.
+        #[ink(message)]
+        pub fn reserve_mint(&mut self, to: AccountId, count: u32) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.reserve_mint(to, count)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn min_balance_to_keep(&self) -> u32 {
+            self.data.min_balance_to_keep()
+        }
+
+        /// Sets the amount of tokens owners must keep when transferring.
+        /// Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_min_balance_to_keep(&mut self, min_balance_to_keep: u32) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_min_balance_to_keep(min_balance_to_keep);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_burn_bps(&self) -> u16 {
+            self.data.transfer_burn_bps()
+        }
+
+        /// Sets the chance, in basis points, for transfers to burn the token
+        /// instead of delivering it, which holders can avoid, see
+        /// `PSP34Data::set_transfer_burn_bps`. Only callable by the owner of
+        /// the contract.
+        #[ink(message)]
+        pub fn set_transfer_burn_bps(&mut self, transfer_burn_bps: u16) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_transfer_burn_bps(transfer_burn_bps)
+        }
+
+        /// Registers or unregisters `operator` as allowed to move any token of
+        /// any owner. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_global_operator(&mut self, operator: AccountId, approved: bool) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_global_operator(operator, approved);
+            Ok(())
+        }
+
+        /// Returns `true` if `operator` may move any token of any owner.
+        #[ink(message)]
+        pub fn is_global_operator(&self, operator: AccountId) -> bool {
+            self.data.is_global_operator(operator)
+        }
+
+        /// Returns a data URI embedding the attributes of the `id` token as
+        /// JSON, see `PSP34Data::data_uri`.
+        #[cfg(feature = "data-uri")]
+        #[ink(message)]
+        pub fn data_uri(&self, id: Id) -> Option<Vec<u8>> {
+            self.data.data_uri(id)
+        }
+
+        #[ink(message)]
+        pub fn operators_for_all(&self, owner: AccountId) -> Vec<AccountId> {
+            self.data.operators_for_all(owner)
+        }
+
+        /// Returns `true` if `owner` approved an operator for all its tokens
+        /// and holds any, see `PSP34Data::has_active_approvals`.
+        #[ink(message)]
+        pub fn has_active_approvals(&self, owner: AccountId) -> bool {
+            self.data.has_active_approvals(owner)
+        }
+
+        #[ink(message)]
+        pub fn transfers_frozen(&self) -> bool {
+            self.data.transfers_frozen()
+        }
+
+        /// Freezes every transfer, mints and burns remaining allowed. Only
+        /// callable by the owner of the contract.
+        #[ink(message)]
+        pub fn freeze_transfers(&mut self) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_transfers_frozen(true);
+            Ok(())
+        }
+
+        /// Lifts the freeze of `freeze_transfers`. Only callable by the
+        /// owner of the contract.
+        #[ink(message)]
+        pub fn unfreeze_transfers(&mut self) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_transfers_frozen(false);
+            Ok(())
+        }
+
+        /// Enables or disables operators delegating their approvals one hop
+        /// further. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_transitive_approvals(&mut self, enabled: bool) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_transitive_approvals(enabled);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn operator_delegators(&self, operator: AccountId) -> Vec<AccountId> {
+            self.data.operator_delegators(operator)
+        }
+
+        /// Delegates (or stops delegating) the approvals the caller received
+        /// as an operator to `operator`, see `PSP34Data::delegate_operator`.
+        #[ink(message)]
+        pub fn delegate_operator(&mut self, operator: AccountId, approved: bool) -> PSP34Result {
+            self.data
+                .delegate_operator(self.env().caller(), operator, approved)
+        }
+
+        /// Stores a chunk of a large attribute of the `id` token. Only
+        /// callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_attribute_chunk(
+            &mut self,
+            id: Id,
+            key: Vec<u8>,
+            chunk_index: u32,
+            data: Vec<u8>,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_attribute_chunk(id, key, chunk_index, data)
+        }
+
+        /// Mints the `id` token to `to` from a voucher signed by the owner of
+        /// the contract, see `PSP34Data::mint_voucher`.
+        #[ink(message)]
+        pub fn mint_voucher(
+            &mut self,
+            to: AccountId,
+            id: Id,
+            attributes: Vec<(Vec<u8>, Vec<u8>)>,
+            signature: [u8; 65],
+        ) -> PSP34Result {
+            let events = self
+                .data
+                .mint_voucher(self.owner, to, id, attributes, signature)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Burns the `id` token of `owner` from a permit they signed, see
+        /// `PSP34Data::burn_permit`.
+        #[ink(message)]
+        pub fn burn_permit(
+            &mut self,
+            owner: AccountId,
+            id: Id,
+            nonce: u64,
+            signature: [u8; 65],
+        ) -> PSP34Result {
+            let events = self.data.burn_permit(owner, id, nonce, signature)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_burn_nonce_used(&self, owner: AccountId, nonce: u64) -> bool {
+            self.data.is_burn_nonce_used(owner, nonce)
+        }
+
+        /// Approves or disapproves `operator` for all the tokens of `owner`
+        /// from a permit they signed, see `PSP34Data::permit`.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            operator: AccountId,
+            approved: bool,
+            deadline: u64,
+            nonce: u64,
+            signature: [u8; 65],
+        ) -> PSP34Result {
+            let events = self
+                .data
+                .permit(owner, operator, approved, deadline, nonce, signature)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_permit_nonce_used(&self, owner: AccountId, nonce: u64) -> bool {
+            self.data.is_permit_nonce_used(owner, nonce)
+        }
+
+        /// Returns the block the `id` token was last transferred or minted at.
+        #[ink(message)]
+        pub fn last_transfer_of(&self, id: Id) -> Option<u64> {
+            self.data.last_transfer_of(id)
+        }
+
+        /// Returns the amount of transfers ever made, mints and burns excluded.
+        #[ink(message)]
+        pub fn transfer_count(&self) -> u128 {
+            self.data.transfer_count()
+        }
+
+        /// Sets the maximum amount of items accepted by batch operations.
+        /// Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_max_batch_size(&mut self, max_batch_size: u32) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_max_batch_size(max_batch_size);
+            Ok(())
+        }
+
+        /// Sets the maximum length of attribute keys and values, 0 meaning
+        /// unlimited. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_max_attribute_lens(
+            &mut self,
+            max_key_len: u32,
+            max_value_len: u32,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_max_attribute_lens(max_key_len, max_value_len);
+            Ok(())
+        }
+
+        /// Mints a new token to `to`, paid with the value transferred along
+        /// with the call.
+        #[ink(message, payable)]
+        pub fn mint_with_payment(&mut self, to: AccountId) -> PSP34Result {
+            let events = self.data.mint_with_payment(
+                self.env().caller(),
+                to,
+                self.env().transferred_value(),
+            )?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn mint_price(&self) -> Balance {
+            self.data.mint_price()
+        }
+
+        /// Sets the price of `mint_with_payment`. Only callable by the owner
+        /// of the contract.
+        #[ink(message)]
+        pub fn set_mint_price(&mut self, mint_price: Balance) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.set_mint_price(mint_price);
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn mint_proceeds(&self) -> Balance {
+            self.data.mint_proceeds()
+        }
+
+        #[ink(message)]
+        pub fn royalty_escrow(&self) -> Balance {
+            self.data.royalty_escrow()
+        }
+
+        /// Returns the balance of the contract not held in the royalty
+        /// escrow.
+        #[ink(message)]
+        pub fn withdrawable(&self) -> Balance {
+            self.data.withdrawable(self.env().balance())
+        }
+
+        /// Pays royalties to the collection, held in escrow until the owner
+        /// withdraws them.
+        #[ink(message, payable)]
+        pub fn deposit_royalties(&mut self) {
+            self.data.add_royalty_escrow(self.env().transferred_value());
+        }
+
+        /// Sets the creator cut of `transfer_paid` sales. Only callable by
+        /// the owner of the contract.
+        #[ink(message)]
+        pub fn set_royalty(
+            &mut self,
+            recipient: Option<AccountId>,
+            royalty_bps: u16,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_royalty(recipient, royalty_bps)
+        }
+
+        /// Sells the `id` token to `to` for the value transferred along with
+        /// the call, which must cover `sale_price`. The royalty recipient
+        /// gets its cut of the value and the owner of the token the rest,
+        /// before the token is moved as by `transfer`: the caller must be
+        /// the owner of the token or approved by them, e.g. a marketplace.
+        ///
+        /// Rejected with `TransferMayBurn` while a transfer burn chance is
+        /// set, as the buyer would pay for a token it may not receive.
+        #[ink(message, payable)]
+        pub fn transfer_paid(&mut self, to: AccountId, id: Id, sale_price: Balance) -> PSP34Result {
+            if self.data.transfer_burn_bps() != 0 {
+                return Err(PSP34Error::TransferMayBurn);
+            }
+
+            let value = self.env().transferred_value();
+            if value < sale_price {
+                return Err(PSP34Error::InsufficientPayment);
+            }
+
+            let owner = self
+                .data
+                .owner_of(id.clone())
+                .ok_or(PSP34Error::TokenNotExists)?;
+            let events = self
+                .data
+                .transfer(self.env().caller(), to, id.clone(), Vec::new())?;
+
+            let (recipient, cut) = self.data.royalty_info(value).unwrap_or((owner, 0));
+            self.transfer_out(recipient, cut)?;
+            self.transfer_out(owner, value - cut)?;
+
+            self.finish_transfer(owner, to, id, Vec::new(), events)
+        }
+
+        /// Transfers the mint proceeds to the owner of the contract, leaving
+        /// the royalty escrow untouched. Only callable by the owner of the
+        /// contract.
+        #[ink(message)]
+        pub fn withdraw_proceeds(&mut self) -> PSP34Result {
+            self.ensure_owner()?;
+            let amount = self.data.take_mint_proceeds(self.env().balance());
+            self.transfer_out(self.owner, amount)
+        }
+
+        /// Transfers the royalty escrow to `recipient`, leaving the mint
+        /// proceeds untouched. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn withdraw_royalties(&mut self, recipient: AccountId) -> PSP34Result {
+            self.ensure_owner()?;
+            let amount = self.data.take_royalty_escrow(self.env().balance());
+            self.transfer_out(recipient, amount)
+        }
+
+        fn transfer_out(&self, recipient: AccountId, amount: Balance) -> PSP34Result {
+            if amount == 0 {
+                return Ok(());
             }
+            self.env()
+                .transfer(recipient, amount)
+                .map_err(|_| PSP34Error::Custom("withdraw failed".into()))
+        }
+
+        /// Sets the minimum amount of blocks between two mints of an account,
+        /// 0 disabling it. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_mint_cooldown_blocks(&mut self, mint_cooldown_blocks: u64) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_mint_cooldown_blocks(mint_cooldown_blocks);
+            Ok(())
         }
 
-        fn emit_events() {
+        /// Sets the first and last blocks minting is allowed at. Only
+        /// callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_mint_window(
+            &mut self,
+            mint_start: Option<u64>,
+            mint_end: Option<u64>,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_mint_window(mint_start, mint_end);
+            Ok(())
+        }
+
+        /// Restricts minting to allowlisted callers, whoever receives the
+        /// tokens, or opens it to everyone. Only callable by the owner of
+        /// the contract.
+        #[ink(message)]
+        pub fn set_allowlist_only(&mut self, allowlist_only: bool) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_allowlist_only(allowlist_only);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn allowlist_remaining(&self, account: AccountId) -> u32 {
+            self.data.allowlist_remaining(account)
+        }
+
+        /// Allows `account` to mint `amount` tokens during the allowlist
+        /// phase. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_allowlist(&mut self, account: AccountId, amount: u32) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_allowlist(account, amount);
+            Ok(())
+        }
+
+        /// Returns the metadata URI of the `id` token.
+        #[ink(message)]
+        pub fn token_uri(&self, id: Id) -> Option<String> {
+            self.data.token_uri(id)
+        }
+
+        /// Sets the placeholder URI and the final base URI. Only callable
+        /// by the owner of the contract, before reveal.
+        #[ink(message)]
+        pub fn set_reveal_uris(
+            &mut self,
+            unrevealed_uri: String,
+            revealed_uri: String,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_reveal_uris(unrevealed_uri, revealed_uri)
+        }
+
+        /// Reveals the final metadata of every token, can't be undone. Only
+        /// callable by the owner of the contract.
+        #[ink(message)]
+        pub fn reveal(&mut self) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.reveal()?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Returns the block the `id` token becomes transferable at.
+        #[ink(message)]
+        pub fn transfer_unlock_of(&self, id: Id) -> Option<u64> {
+            self.data.transfer_unlock_of(id)
+        }
+
+        /// Prevents the `id` token from being transferred before the
+        /// `unlock_block` block. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_transfer_unlock_block(&mut self, id: Id, unlock_block: u64) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_transfer_unlock_block(id, unlock_block)
+        }
+
+        /// Mints a new non-transferable token to `account`. Only callable by
+        /// the owner of the contract.
+        #[ink(message)]
+        pub fn mint_soulbound(&mut self, account: AccountId) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.mint_soulbound(self.env().caller(), account)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_soulbound(&self, id: Id) -> bool {
+            self.data.is_soulbound(id)
+        }
+
+        #[ink(message)]
+        pub fn max_balance_per_owner(&self) -> Option<u32> {
+            self.data.max_balance_per_owner()
+        }
+
+        /// Sets the maximum amount of tokens a single account can hold,
+        /// `None` meaning unlimited. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_max_balance_per_owner(
+            &mut self,
+            max_balance_per_owner: Option<u32>,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_max_balance_per_owner(max_balance_per_owner);
+            Ok(())
+        }
+
+        /// Reclaims the `id` token from `from` to `to` regardless of
+        /// approvals, frozen transfers and transfer locks, see
+        /// `PSP34Data::admin_transfer`. Only callable by the owner of the
+        /// contract.
+        #[ink(message)]
+        pub fn admin_transfer(&mut self, from: AccountId, to: AccountId, id: Id) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.admin_transfer(from, to, id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Changes the id of the `old_id` token to `new_id`, see
+        /// `PSP34Data::rekey`. Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn rekey(&mut self, old_id: Id, new_id: Id) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.rekey(old_id, new_id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn collection_name(&self) -> Option<Vec<u8>> {
+            self.data.collection_name()
+        }
+
+        #[ink(message)]
+        pub fn collection_symbol(&self) -> Option<Vec<u8>> {
+            self.data.collection_symbol()
+        }
+
+        #[ink(message)]
+        pub fn base_uri(&self) -> Option<Vec<u8>> {
+            self.data.base_uri()
+        }
+
+        /// Sets the `key` attribute of the collection itself, e.g. its name.
+        /// Only callable by the owner of the contract.
+        #[ink(message)]
+        pub fn set_collection_attribute(&mut self, key: Vec<u8>, value: Vec<u8>) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.set_collection_attribute(key, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn default_attributes(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+            self.data.default_attributes()
+        }
+
+        /// Sets the attributes applied to every minted token. Only callable
+        /// by the owner of the contract.
+        #[ink(message)]
+        pub fn set_default_attributes(
+            &mut self,
+            attributes: Vec<(Vec<u8>, Vec<u8>)>,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_default_attributes(attributes)
+        }
+
+        /// Mints a token with `attributes` to `account` once per
+        /// `external_id`, returning the token minted the first time on
+        /// retries, see `PSP34Data::mint_idempotent`. Only callable by the
+        /// owner of the contract.
+        #[ink(message)]
+        pub fn mint_idempotent(
+            &mut self,
+            account: AccountId,
+            external_id: Vec<u8>,
+            attributes: Vec<(Vec<u8>, Vec<u8>)>,
+        ) -> PSP34Result<Id> {
+            self.ensure_owner()?;
+            let events = self.data.mint_idempotent(
+                self.env().caller(),
+                account,
+                external_id.clone(),
+                attributes,
+            )?;
+            self.emit_events(events);
+            self.data
+                .external_mint(external_id)
+                .ok_or(PSP34Error::TokenNotExists)
+        }
+
+        #[ink(message)]
+        pub fn external_mint(&self, external_id: Vec<u8>) -> Option<Id> {
+            self.data.external_mint(external_id)
+        }
+
+        /// Mints one token to each of the `recipients`. Only callable by the
+        /// owner of the contract.
+        #[ink(message)]
+        pub fn airdrop(&mut self, recipients: Vec<AccountId>) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self.data.airdrop(recipients)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Mints a new token to `account` with traits picked from
+        /// `trait_pools`, see `PSP34Data::mint_random`. Only callable by the
+        /// owner of the contract.
+        #[ink(message)]
+        pub fn mint_random(
+            &mut self,
+            account: AccountId,
+            trait_pools: Vec<(Vec<u8>, Vec<Vec<u8>>)>,
+        ) -> PSP34Result {
+            self.ensure_owner()?;
+            let events = self
+                .data
+                .mint_random(self.env().caller(), account, trait_pools)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_token_metadata_frozen(&self, id: Id) -> bool {
+            self.data.is_token_metadata_frozen(id)
+        }
+
+        /// Freezes the attributes of the `id` token for good. Only callable
+        /// by the owner of the contract.
+        #[ink(message)]
+        pub fn freeze_token_metadata(&mut self, id: Id) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.freeze_token_metadata(id)
+        }
+
+        #[ink(message)]
+        pub fn token_info(&self, id: Id) -> Option<TokenInfo> {
+            self.data.token_info(id)
+        }
+
+        /// Returns a page of at most `max_batch_size` attributes of the `id`
+        /// token, starting from the `from_index`-th one, for reading past
+        /// the bound of `all_attributes`.
+        #[ink(message)]
+        pub fn attributes_from(&self, id: Id, from_index: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+            self.data.attributes_from(id, from_index)
+        }
+
+        #[ink(message)]
+        pub fn attribute_count(&self, id: Id) -> u32 {
+            self.data.attribute_count(id)
+        }
+
+        /// Same as `transfer`, without calling `PSP34Receiver::on_received`
+        /// when `to` is a contract, e.g. to deposit into a known vault.
+        ///
+        /// A contract not expecting tokens may have no way to move them
+        /// out again, in which case they are lost for good.
+        #[ink(message)]
+        pub fn transfer_unsafe(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> PSP34Result {
+            let events = self.data.transfer(self.env().caller(), to, id, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Completes a transfer of the `id` token from `from` to `to` which
+        /// produced `events`: calls `ensure_received` unless the token got
+        /// burnt on the way, then emits the events. Every safe transfer
+        /// path goes through here so that none skips the receiver check.
+        fn finish_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: Id,
+            data: Vec<u8>,
+            events: Vec<PSP34Event>,
+        ) -> PSP34Result {
+            if self.data.owner_of(id.clone()) == Some(to) {
+                self.ensure_received(from, to, id, data)?;
+            }
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Calls `PSP34Receiver::on_received` on `to` if it is a contract,
+        /// failing if the call fails or returns an error.
+        fn ensure_received(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            id: Id,
+            data: Vec<u8>,
+        ) -> PSP34Result {
+            if !self.env().is_contract(&to) {
+                return Ok(());
+            }
+
+            let result = build_call::<DefaultEnvironment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector_bytes!(
+                        "PSP34Receiver::on_received"
+                    )))
+                    .push_arg(self.env().caller())
+                    .push_arg(from)
+                    .push_arg(id)
+                    .push_arg(data),
+                )
+                .returns::<PSP34Result>()
+                .try_invoke();
+
+            if !matches!(result, Ok(Ok(Ok(())))) {
+                return Err(PSP34Error::SafeTransferCheckFailed(
+                    "'to' contract rejected the transfer".into(),
+                ));
+            }
+            Ok(())
+        }
+
+        fn emit_events(&self, events: Vec<PSP34Event>) {
             for event in events {
                 match event {
-                    PSP34Event::Transfer { from, to, id } => {
-                        self.env().emit_event(Transfer { from, to, id })
-                    }
+                    PSP34Event::Transfer {
+                        from,
+                        to,
+                        id,
+                        operator,
+                    } => self.env().emit_event(Transfer {
+                        from,
+                        to,
+                        id,
+                        operator,
+                    }),
+                    PSP34Event::Burn { from, id } => self.env().emit_event(Burn { from, id }),
                     PSP34Event::Approval {
                         owner,
                         operator,
@@ -52,39 +868,126 @@ mod token {
                     PSP34Event::AttributeSet { id, key, data } => {
                         self.env().emit_event(AttributeSet { id, key, data })
                     }
+                    PSP34Event::SoldOut { total_supply } => {
+                        self.env().emit_event(SoldOut { total_supply })
+                    }
+                    PSP34Event::Revealed { revealed_uri } => {
+                        self.env().emit_event(Revealed { revealed_uri })
+                    }
+                    PSP34Event::MintPriceChanged { old, new } => {
+                        self.env().emit_event(MintPriceChanged { old, new })
+                    }
+                    PSP34Event::AdminTransfer { from, to, id } => {
+                        self.env().emit_event(AdminTransfer { from, to, id })
+                    }
+                    PSP34Event::Rekeyed { old_id, new_id } => {
+                        self.env().emit_event(Rekeyed { old_id, new_id })
+                    }
+                    PSP34Event::DelegateChanged {
+                        delegator,
+                        from_delegate,
+                        to_delegate,
+                    } => self.env().emit_event(DelegateChanged {
+                        delegator,
+                        from_delegate,
+                        to_delegate,
+                    }),
+                    PSP34Event::DelegateVotesChanged {
+                        delegate,
+                        previous_votes,
+                        new_votes,
+                    } => self.env().emit_event(DelegateVotesChanged {
+                        delegate,
+                        previous_votes,
+                        new_votes,
+                    }),
                 }
             }
         }
     }
 
     #[ink(event)]
-    pub struct Approval {
-        owner: AccountId,
-        operator: AccountId,
-        id: Option<Id>,
-        approved: bool,
+    pub struct Approval {
+        owner: AccountId,
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        id: Id,
+        #[ink(topic)]
+        operator: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        id: Id,
+    }
+
+    #[ink(event)]
+    pub struct AttributeSet {
+        #[ink(topic)]
+        id: Id,
+        #[ink(topic)]
+        key: Vec<u8>,
+        data: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct SoldOut {
+        total_supply: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Revealed {
+        revealed_uri: String,
+    }
+
+    #[ink(event)]
+    pub struct MintPriceChanged {
+        old: Balance,
+        new: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AdminTransfer {
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+    }
+
+    #[ink(event)]
+    pub struct Rekeyed {
+        #[ink(topic)]
+        old_id: Id,
+        #[ink(topic)]
+        new_id: Id,
     }
 
     #[ink(event)]
-    pub struct Transfer {
-        from: Option<AccountId>,
-        to: Option<AccountId>,
-        id: Id,
+    pub struct DelegateChanged {
+        delegator: AccountId,
+        from_delegate: Option<AccountId>,
+        to_delegate: AccountId,
     }
 
     #[ink(event)]
-    pub struct AttributeSet {
-        id: Id,
-        key: Vec<u8>,
-        data: Vec<u8>,
+    pub struct DelegateVotesChanged {
+        delegate: AccountId,
+        previous_votes: u128,
+        new_votes: u128,
     }
 
     impl PSP34 for Token {
         #[ink(message)]
         fn collection_id(&self) -> Id {
-            let account_id = self.env().account_id();
-            let collection_id = Id::Bytes(<_ as AsRef<[u8; 32]>>::as_ref(&account_id).to_vec());
-            collection_id
+            self.data.collection_id()
         }
 
         #[ink(message)]
@@ -97,18 +1000,33 @@ mod token {
             self.data.owner_of(id)
         }
 
+        #[ink(message)]
+        fn owner_of_or_zero(&self, id: Id) -> AccountId {
+            self.data.owner_of_or_zero(id)
+        }
+
+        #[ink(message)]
+        fn owners_of(&self, ids: Vec<Id>) -> PSP34Result<Vec<Option<AccountId>>> {
+            self.data.owners_of(ids)
+        }
+
         #[ink(message)]
         fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
             self.data.allowance(owner, operator, id)
         }
 
         #[ink(message)]
-        fn approve(
-            &mut self,
+        fn allowance_batch(
+            &self,
+            owner: AccountId,
             operator: AccountId,
-            id: Option<Id>,
-            approved: bool,
-        ) -> Result<(), PSP34Error> {
+            ids: Vec<Id>,
+        ) -> PSP34Result<Vec<bool>> {
+            self.data.allowance_batch(owner, operator, ids)
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, operator: AccountId, id: Option<Id>, approved: bool) -> PSP34Result {
             let events = self
                 .data
                 .approve(self.env().caller(), operator, id, approved)?;
@@ -117,10 +1035,15 @@ mod token {
         }
 
         #[ink(message)]
-        fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error> {
-            let events = self.data.transfer(self.env().caller(), to, id, data)?;
-            self.emit_events(events);
-            Ok(())
+        fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> PSP34Result {
+            let from = self
+                .data
+                .owner_of(id.clone())
+                .ok_or(PSP34Error::TokenNotExists)?;
+            let events = self
+                .data
+                .transfer(self.env().caller(), to, id.clone(), data.clone())?;
+            self.finish_transfer(from, to, id, data, events)
         }
 
         #[ink(message)]
@@ -130,10 +1053,11 @@ mod token {
             to: AccountId,
             id: Id,
             data: Vec<u8>,
-        ) -> Result<(), PSP34Error> {
-            let events = self.data.transfer_from(from, to, id, data)?;
-            self.emit_events(events);
-            Ok(())
+        ) -> PSP34Result {
+            let events =
+                self.data
+                    .transfer_from(self.env().caller(), from, to, id.clone(), data.clone())?;
+            self.finish_transfer(from, to, id, data, events)
         }
 
         #[ink(message)]
@@ -142,12 +1066,48 @@ mod token {
         }
     }
 
+    impl PSP34Interop for Token {
+        #[ink(message)]
+        fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.data.is_approved_for_all(owner, operator)
+        }
+
+        #[ink(message)]
+        fn get_approved(&self, id: Id) -> Option<AccountId> {
+            self.data.get_approved(id)
+        }
+
+        #[ink(message)]
+        fn approval_status(&self, owner: AccountId, operator: AccountId) -> ApprovalStatus {
+            self.data.approval_status(owner, operator)
+        }
+
+        #[ink(message)]
+        fn approve_token(&mut self, operator: AccountId, id: Id, approved: bool) -> PSP34Result {
+            PSP34::approve(self, operator, Some(id), approved)
+        }
+
+        #[ink(message)]
+        fn approve_all(&mut self, operator: AccountId, approved: bool) -> PSP34Result {
+            PSP34::approve(self, operator, None, approved)
+        }
+
+        #[ink(message)]
+        fn operators_for_token(&self, owner: AccountId, id: Id) -> Vec<AccountId> {
+            self.data.operators_for_token(owner, id)
+        }
+    }
+
+    /// Free mints, only callable by the owner of the contract. Anyone else
+    /// mints through `mint_with_payment`.
     impl PSP34Mintable for Token {
         #[ink(message)]
-        fn mint(&mut self, account: AccountId) -> Result<(), PSP34Error> {
-            let events = self.data.mint(account)?;
+        fn mint(&mut self, account: AccountId) -> PSP34Result<Id> {
+            self.ensure_owner()?;
+            let id = self.data.next_token_id()?;
+            let events = self.data.mint(self.env().caller(), account)?;
             self.emit_events(events);
-            Ok(())
+            Ok(id)
         }
 
         #[ink(message)]
@@ -155,27 +1115,205 @@ mod token {
             &mut self,
             account: AccountId,
             attributes: Vec<(Vec<u8>, Vec<u8>)>,
-        ) -> Result<(), PSP34Error> {
-            let events = self.data.mint_with_attributes(account, attributes)?;
+        ) -> PSP34Result<Id> {
+            self.ensure_owner()?;
+            let id = self.data.next_token_id()?;
+            let events =
+                self.data
+                    .mint_with_attributes(self.env().caller(), account, attributes)?;
             self.emit_events(events);
-            Ok(())
+            Ok(id)
+        }
+
+        #[ink(message)]
+        fn mint_with_uri(&mut self, account: AccountId, uri: Vec<u8>) -> PSP34Result<Id> {
+            self.ensure_owner()?;
+            let id = self.data.next_token_id()?;
+            let events = self.data.mint_with_uri(self.env().caller(), account, uri)?;
+            self.emit_events(events);
+            Ok(id)
+        }
+
+        #[ink(message)]
+        fn next_token_id(&self) -> PSP34Result<Id> {
+            self.data.next_token_id()
+        }
+    }
+
+    impl PSP34Capped for Token {
+        #[ink(message)]
+        fn cap(&self) -> Balance {
+            self.data.cap()
+        }
+
+        #[ink(message)]
+        fn remaining_supply(&self) -> Balance {
+            self.data.remaining_supply()
+        }
+
+        #[ink(message)]
+        fn is_sold_out(&self) -> bool {
+            self.data.is_sold_out()
         }
     }
 
     impl PSP34Burnable for Token {
         #[ink(message)]
-        fn burn(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error> {
-            let events = self.data.burn(account, id)?;
+        fn burn(&mut self, account: AccountId, id: Id) -> PSP34Result {
+            let events = self.data.burn_from(self.env().caller(), account, id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34Swap for Token {
+        #[ink(message)]
+        fn swap(&mut self, old_id: Id, new_attributes: Vec<(Vec<u8>, Vec<u8>)>) -> PSP34Result {
+            let events = self
+                .data
+                .swap(self.env().caller(), old_id, new_attributes)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34Votes for Token {
+        #[ink(message)]
+        fn get_votes(&self, account: AccountId) -> u128 {
+            self.data.get_votes(account)
+        }
+
+        #[ink(message)]
+        fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.data.delegates(account)
+        }
+
+        #[ink(message)]
+        fn delegate(&mut self, to: AccountId) -> PSP34Result {
+            let events = self.data.delegate(self.env().caller(), to);
             self.emit_events(events);
             Ok(())
         }
     }
 
+    impl PSP34TransferWithAttribute for Token {
+        #[ink(message)]
+        fn transfer_with_attribute(
+            &mut self,
+            to: AccountId,
+            id: Id,
+            data: Vec<u8>,
+            key: Vec<u8>,
+            value: Vec<u8>,
+        ) -> PSP34Result {
+            let from = self
+                .data
+                .owner_of(id.clone())
+                .ok_or(PSP34Error::TokenNotExists)?;
+            let events = self.data.transfer_with_attribute(
+                self.env().caller(),
+                to,
+                id.clone(),
+                data.clone(),
+                key,
+                value,
+            )?;
+            self.finish_transfer(from, to, id, data, events)
+        }
+    }
+
     impl PSP34Metadata for Token {
         #[ink(message)]
         fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
             self.data.get_attribute(id, key)
         }
+
+        #[ink(message)]
+        fn try_get_attribute(&self, id: Id, key: Vec<u8>) -> PSP34Result<Option<Vec<u8>>> {
+            self.data.try_get_attribute(id, key)
+        }
+
+        #[ink(message)]
+        fn get_attribute_full(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
+            self.data.get_attribute_full(id, key)
+        }
+
+        #[ink(message)]
+        fn all_attributes(&self, id: Id) -> Vec<(Vec<u8>, Vec<u8>)> {
+            self.data.all_attributes(id)
+        }
+
+        #[ink(message)]
+        fn get_attribute_for_tokens(
+            &self,
+            ids: Vec<Id>,
+            key: Vec<u8>,
+        ) -> PSP34Result<Vec<Option<Vec<u8>>>> {
+            self.data.get_attribute_for_tokens(ids, key)
+        }
+    }
+
+    impl PSP34MetadataHash for Token {
+        #[ink(message)]
+        fn collection_metadata_hash(&self) -> Option<[u8; 32]> {
+            self.data.collection_metadata_hash()
+        }
+
+        #[ink(message)]
+        fn set_collection_metadata_hash(&mut self, hash: [u8; 32]) -> PSP34Result {
+            self.ensure_owner()?;
+            self.data.set_collection_metadata_hash(hash)
+        }
+
+        #[ink(message)]
+        fn verify_metadata(&self, preimage: Vec<u8>) -> bool {
+            self.data.verify_metadata(preimage)
+        }
+    }
+
+    impl PSP34Snapshot for Token {
+        #[ink(message)]
+        fn snapshot(&mut self) -> PSP34Result<u32> {
+            self.ensure_owner()?;
+            Ok(self.data.snapshot())
+        }
+
+        #[ink(message)]
+        fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> PSP34Result<u32> {
+            self.data.balance_of_at(account, snapshot_id)
+        }
+
+        #[ink(message)]
+        fn total_supply_at(&self, snapshot_id: u32) -> PSP34Result<Balance> {
+            self.data.total_supply_at(snapshot_id)
+        }
+    }
+
+    impl PSP34MutableMetadata for Token {
+        #[ink(message)]
+        fn set_attribute(&mut self, id: Id, key: Vec<u8>, value: Vec<u8>) -> PSP34Result {
+            let events = self
+                .data
+                .set_attributes(self.env().caller(), id, vec![(key, value)])?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn set_attributes(&mut self, id: Id, attributes: Vec<(Vec<u8>, Vec<u8>)>) -> PSP34Result {
+            let events = self
+                .data
+                .set_attributes(self.env().caller(), id, attributes)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn remove_attribute(&mut self, id: Id, key: Vec<u8>) -> PSP34Result {
+            let events = self.data.remove_attribute(self.env().caller(), id, key)?;
+            self.emit_events(events);
+            Ok(())
+        }
     }
 
     impl PSP34Enumerable for Token {
@@ -184,9 +1322,502 @@ mod token {
             self.data.token_by_index(index)
         }
 
+        #[ink(message)]
+        fn next_existing_token(&self, from_index: u128) -> Option<(u128, Id)> {
+            self.data.next_existing_token(from_index)
+        }
+
         #[ink(message)]
         fn owners_token_by_index(&self, owner: AccountId, index: u128) -> Option<Id> {
             self.data.owners_token_by_index(owner, index)
         }
+
+        #[ink(message)]
+        fn index_of_token(&self, id: Id) -> Option<u128> {
+            self.data.index_of_token(id)
+        }
+
+        #[ink(message)]
+        fn index_of_owner_token(&self, owner: AccountId, id: Id) -> Option<u128> {
+            self.data.index_of_owner_token(owner, id)
+        }
+    }
+
+    impl PSP34Introspection for Token {
+        #[ink(message)]
+        fn supports_interface(&self, interface_id: u32) -> bool {
+            SUPPORTED_INTERFACES.contains(&interface_id)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test;
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        #[ink::test]
+        fn new_with_config_applies_the_config() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let config = PSP34Config::builder()
+                .owner(accounts.bob)
+                .max_supply(1)
+                .build()
+                .unwrap();
+            let mut token = Token::new_with_config(Box::new(config));
+
+            assert!(PSP34Mintable::mint(&mut token, accounts.alice).is_err());
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            PSP34Mintable::mint(&mut token, accounts.bob).unwrap();
+            assert_eq!(
+                PSP34Mintable::mint(&mut token, accounts.bob),
+                Err(PSP34Error::ReachedMaxSupply)
+            );
+        }
+
+        #[ink::test]
+        fn mint_is_only_callable_by_the_owner() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                PSP34Mintable::mint(&mut token, accounts.bob),
+                Err(PSP34Error::NotOwner)
+            );
+            assert_eq!(
+                PSP34Mintable::mint_with_attributes(&mut token, accounts.bob, vec![]),
+                Err(PSP34Error::NotOwner)
+            );
+            assert_eq!(
+                PSP34Mintable::mint_with_uri(&mut token, accounts.bob, b"ipfs://x".to_vec()),
+                Err(PSP34Error::NotOwner)
+            );
+            assert_eq!(PSP34::total_supply(&token), 0);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                PSP34Mintable::mint(&mut token, accounts.bob),
+                Ok(Id::U128(0))
+            );
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "off-chain environment does not support contract invocation")]
+        fn transfer_from_calls_the_receiver() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+            test::set_contract::<DefaultEnvironment>(accounts.charlie);
+
+            let _ = PSP34::transfer_from(
+                &mut token,
+                accounts.alice,
+                accounts.charlie,
+                Id::U128(0),
+                vec![],
+            );
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "off-chain environment does not support contract invocation")]
+        fn transfer_with_attribute_calls_the_receiver() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+            test::set_contract::<DefaultEnvironment>(accounts.charlie);
+
+            let _ = PSP34TransferWithAttribute::transfer_with_attribute(
+                &mut token,
+                accounts.charlie,
+                Id::U128(0),
+                vec![],
+                b"level".to_vec(),
+                b"2".to_vec(),
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_to_an_account_skips_the_receiver() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+
+            PSP34::transfer_from(
+                &mut token,
+                accounts.alice,
+                accounts.bob,
+                Id::U128(0),
+                vec![],
+            )
+            .unwrap();
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn mint_with_payment_rejects_underpayment() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            token.set_mint_price(100).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<DefaultEnvironment>(99);
+            assert_eq!(
+                token.mint_with_payment(accounts.bob),
+                Err(PSP34Error::InsufficientPayment)
+            );
+            assert_eq!(PSP34::balance_of(&token, accounts.bob), 0);
+
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            token.mint_with_payment(accounts.bob).unwrap();
+            assert_eq!(PSP34::balance_of(&token, accounts.bob), 1);
+            assert_eq!(token.mint_proceeds(), 100);
+        }
+
+        #[ink::test]
+        fn transfer_unchecked_is_only_callable_by_the_owner() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.bob).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token
+                .transfer_unchecked(accounts.bob, accounts.charlie, Id::U128(0))
+                .is_err());
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.bob));
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            token
+                .transfer_unchecked(accounts.bob, accounts.charlie, Id::U128(0))
+                .unwrap();
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.charlie));
+        }
+
+        #[ink::test]
+        fn approve_token_and_approve_all_delegate_to_approve() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+
+            token
+                .approve_token(accounts.bob, Id::U128(0), true)
+                .unwrap();
+            assert!(PSP34::allowance(
+                &token,
+                accounts.alice,
+                accounts.bob,
+                Some(Id::U128(0))
+            ));
+            assert!(!token.is_approved_for_all(accounts.alice, accounts.bob));
+
+            token.approve_all(accounts.charlie, true).unwrap();
+            assert!(token.is_approved_for_all(accounts.alice, accounts.charlie));
+            token.approve_all(accounts.charlie, false).unwrap();
+            assert!(!token.is_approved_for_all(accounts.alice, accounts.charlie));
+
+            assert_eq!(
+                token.approve_all(accounts.alice, true),
+                Err(PSP34Error::SelfApprove)
+            );
+        }
+
+        #[ink::test]
+        fn supports_the_declared_interfaces_only() {
+            let token = Token::new();
+
+            for interface_id in SUPPORTED_INTERFACES {
+                assert!(token.supports_interface(*interface_id));
+            }
+            assert!(!token.supports_interface(PSP34_WRAPPER_INTERFACE_ID));
+            assert!(!token.supports_interface(PSP34_RECEIVER_INTERFACE_ID));
+            assert!(!token.supports_interface(0xffff_ffff));
+        }
+
+        #[ink::test]
+        fn attribute_set_is_indexed_by_id_and_key() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+
+            PSP34MutableMetadata::set_attribute(
+                &mut token,
+                Id::U128(0),
+                b"level".to_vec(),
+                b"2".to_vec(),
+            )
+            .unwrap();
+
+            let event = test::recorded_events().last().unwrap();
+            // the event signature, `id` and `key`
+            assert_eq!(event.topics.len(), 3);
+        }
+
+        #[ink::test]
+        fn transfer_unsafe_skips_the_receiver() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+            test::set_contract::<DefaultEnvironment>(accounts.charlie);
+
+            token
+                .transfer_unsafe(accounts.charlie, Id::U128(0), vec![])
+                .unwrap();
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.charlie));
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "off-chain environment does not support contract invocation")]
+        fn transfer_calls_the_receiver() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+            test::set_contract::<DefaultEnvironment>(accounts.charlie);
+
+            let _ = PSP34::transfer(&mut token, accounts.charlie, Id::U128(0), vec![]);
+        }
+
+        #[ink::test]
+        fn admin_transfer_claws_back_for_the_owner_only() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.bob).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token
+                .admin_transfer(accounts.bob, accounts.charlie, Id::U128(0))
+                .is_err());
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            token
+                .admin_transfer(accounts.bob, accounts.alice, Id::U128(0))
+                .unwrap();
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.alice));
+            assert_eq!(PSP34::balance_of(&token, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn transfer_paid_splits_the_payment() {
+            let accounts = accounts();
+            let balance =
+                |account| test::get_account_balance::<DefaultEnvironment>(account).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            token.set_royalty(Some(accounts.django), 1_000).unwrap();
+            PSP34Mintable::mint(&mut token, accounts.bob).unwrap();
+            let (bob, django) = (balance(accounts.bob), balance(accounts.django));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<DefaultEnvironment>(99);
+            assert_eq!(
+                token.transfer_paid(accounts.charlie, Id::U128(0), 100),
+                Err(PSP34Error::InsufficientPayment)
+            );
+
+            test::set_account_balance::<DefaultEnvironment>(
+                test::callee::<DefaultEnvironment>(),
+                100,
+            );
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            token
+                .transfer_paid(accounts.charlie, Id::U128(0), 100)
+                .unwrap();
+            assert_eq!(balance(accounts.django), django + 10);
+            assert_eq!(balance(accounts.bob), bob + 90);
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.charlie));
+        }
+
+        #[ink::test]
+        fn transfer_topic_is_the_approved_operator() {
+            use ink::env::hash::{Blake2x256, CryptoHash};
+            use ink::env::topics::PrefixedValue;
+            use scale::Encode;
+
+            let operator_topic = |operator: Option<AccountId>| {
+                let encoded = PrefixedValue {
+                    value: &operator,
+                    prefix: b"Token::Transfer::operator",
+                }
+                .encode();
+                let mut topic = [0u8; 32];
+                if encoded.len() <= 32 {
+                    topic[..encoded.len()].copy_from_slice(&encoded);
+                } else {
+                    Blake2x256::hash(&encoded, &mut topic);
+                }
+                topic.to_vec()
+            };
+
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+            PSP34::approve(&mut token, accounts.bob, Some(Id::U128(0)), true).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            PSP34::transfer_from(
+                &mut token,
+                accounts.alice,
+                accounts.charlie,
+                Id::U128(0),
+                vec![],
+            )
+            .unwrap();
+            let event = test::recorded_events().last().unwrap();
+            assert_eq!(event.topics[1], operator_topic(Some(accounts.bob)));
+
+            // a direct transfer by the owner has no operator
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            PSP34::transfer(&mut token, accounts.django, Id::U128(0), vec![]).unwrap();
+            let event = test::recorded_events().last().unwrap();
+            assert_eq!(event.topics[1], operator_topic(None));
+        }
+
+        #[ink::test]
+        fn mints_return_the_id_they_assign() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+
+            let first = PSP34Mintable::mint(&mut token, accounts.bob).unwrap();
+            let second = PSP34Mintable::mint_with_attributes(
+                &mut token,
+                accounts.charlie,
+                vec![(b"level".to_vec(), b"1".to_vec())],
+            )
+            .unwrap();
+
+            assert_ne!(first, second);
+            assert_eq!(PSP34::owner_of(&token, first), Some(accounts.bob));
+            assert_eq!(PSP34::owner_of(&token, second), Some(accounts.charlie));
+        }
+
+        #[ink::test]
+        fn withdrawable_holds_proceeds_but_not_the_escrow() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            token.set_mint_price(100).unwrap();
+            let contract = accounts.frank;
+            test::set_callee::<DefaultEnvironment>(contract);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            token.mint_with_payment(accounts.bob).unwrap();
+            test::set_value_transferred::<DefaultEnvironment>(40);
+            token.deposit_royalties();
+            test::set_account_balance::<DefaultEnvironment>(contract, 140);
+
+            assert_eq!(token.mint_proceeds(), 100);
+            assert_eq!(token.royalty_escrow(), 40);
+            assert_eq!(token.withdrawable(), 100);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            token.withdraw_proceeds().unwrap();
+            assert_eq!(token.withdrawable(), 0);
+            assert_eq!(token.royalty_escrow(), 40);
+        }
+
+        #[ink::test]
+        fn set_mint_price_emits_the_old_and_new_price() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            token.set_mint_price(100).unwrap();
+            token.set_mint_price(250).unwrap();
+            assert_eq!(token.mint_price(), 250);
+
+            type Event = <Token as ink::reflect::ContractEventBase>::Type;
+            let event = test::recorded_events().last().unwrap();
+            let decoded = <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap();
+            assert!(matches!(
+                decoded,
+                Event::MintPriceChanged(MintPriceChanged { old: 100, new: 250 })
+            ));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token.set_mint_price(0).is_err());
+            assert_eq!(token.mint_price(), 250);
+        }
+
+        #[ink::test]
+        fn frozen_transfers_still_allow_mints() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            token.freeze_transfers().unwrap();
+
+            PSP34Mintable::mint(&mut token, accounts.alice).unwrap();
+            assert_eq!(
+                PSP34::transfer(&mut token, accounts.bob, Id::U128(0), vec![]),
+                Err(PSP34Error::TransfersFrozen)
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token.unfreeze_transfers().is_err());
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            token.unfreeze_transfers().unwrap();
+            PSP34::transfer(&mut token, accounts.bob, Id::U128(0), vec![]).unwrap();
+        }
+
+        #[ink::test]
+        fn mint_idempotent_mints_once_per_external_id() {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+
+            let first = token
+                .mint_idempotent(accounts.bob, b"order-1".to_vec(), vec![])
+                .unwrap();
+            let retry = token
+                .mint_idempotent(accounts.charlie, b"order-1".to_vec(), vec![])
+                .unwrap();
+
+            assert_eq!(first, retry);
+            assert_eq!(PSP34::total_supply(&token), 1);
+            assert_eq!(PSP34::owner_of(&token, first), Some(accounts.bob));
+            assert_eq!(PSP34::balance_of(&token, accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn transfer_paid_is_rejected_while_transfers_may_burn() {
+            let accounts = accounts();
+            let balance =
+                |account| test::get_account_balance::<DefaultEnvironment>(account).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = Token::new();
+            token.set_transfer_burn_bps(10_000).unwrap();
+            PSP34Mintable::mint(&mut token, accounts.bob).unwrap();
+            let bob = balance(accounts.bob);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            test::set_account_balance::<DefaultEnvironment>(
+                test::callee::<DefaultEnvironment>(),
+                100,
+            );
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            assert_eq!(
+                token.transfer_paid(accounts.charlie, Id::U128(0), 100),
+                Err(PSP34Error::TransferMayBurn)
+            );
+            assert_eq!(balance(accounts.bob), bob);
+            assert_eq!(PSP34::owner_of(&token, Id::U128(0)), Some(accounts.bob));
+        }
     }
 }
@@ -1,8 +1,8 @@
 use ink::{prelude::vec::Vec, primitives::AccountId};
 
-use crate::PSP34Error;
+use crate::PSP34Result;
 
-use crate::types::{Balance, Id};
+use crate::types::{ApprovalStatus, Balance, Id};
 
 #[ink::trait_definition]
 pub trait PSP34 {
@@ -22,11 +22,39 @@ pub trait PSP34 {
     #[ink(message)]
     fn owner_of(&self, id: Id) -> Option<AccountId>;
 
+    /// Returns the owner of the token, or the dead address (the zero
+    /// address by default) if it doesn't exist.
+    #[ink(message)]
+    fn owner_of_or_zero(&self, id: Id) -> AccountId;
+
+    /// Returns the owner of each of the `ids`, positionally aligned with
+    /// them, `None` for the ones that weren't minted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if too many ids are given.
+    #[ink(message)]
+    fn owners_of(&self, ids: Vec<Id>) -> PSP34Result<Vec<Option<AccountId>>>;
+
     /// Returns `true` if the operator is approved by the owner to withdraw `id` token.
     /// If `id` is `None`, returns `true` if the operator is approved to withdraw all owner's tokens.
     #[ink(message)]
     fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool;
 
+    /// Returns the `allowance` of `operator` for each of the `ids` of
+    /// `owner`, positionally aligned with them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if too many ids are given.
+    #[ink(message)]
+    fn allowance_batch(
+        &self,
+        owner: AccountId,
+        operator: AccountId,
+        ids: Vec<Id>,
+    ) -> PSP34Result<Vec<bool>>;
+
     /// Approves `operator` to withdraw  the `id` token from the caller's account.
     /// If `id` is `None` approves or disapproves the operator for all tokens of the caller.
     ///
@@ -36,17 +64,20 @@ pub trait PSP34 {
     ///
     /// Returns `SelfApprove` error if it is self approve.
     ///
-    /// Returns `NotApproved` error if caller is not owner of `id`.
+    /// Returns `NotOwner` error if caller has no relationship to `id`: it
+    /// is neither its owner nor holds any approval from them.
+    ///
+    /// Returns `NotApproved` error if caller holds an approval which
+    /// doesn't cover `id`, e.g. for another token of the owner, or is a
+    /// global or delegated operator.
     #[ink(message)]
-    fn approve(
-        &mut self,
-        operator: AccountId,
-        id: Option<Id>,
-        approved: bool,
-    ) -> Result<(), PSP34Error>;
+    fn approve(&mut self, operator: AccountId, id: Option<Id>, approved: bool) -> PSP34Result;
 
     /// Transfer approved or owned token from caller.
     ///
+    /// If `to` is a contract, its `PSP34Receiver::on_received` message is
+    /// called once the token is moved and must succeed.
+    ///
     /// On success a `Transfer` event is emitted.
     ///
     /// # Errors
@@ -55,10 +86,41 @@ pub trait PSP34 {
     ///
     /// Returns `NotApproved` error if `from` doesn't have allowance for transferring.
     ///
-    /// Returns `SafeTransferCheckFailed` error if `to` doesn't accept transfer.
+    /// Returns `SafeTransferCheckFailed` error if `to` doesn't accept transfer,
+    /// including when it is a contract rejecting or not implementing
+    /// `PSP34Receiver`.
+    ///
+    /// Returns `BalanceCapExceeded` error if `to` already holds the maximum
+    /// amount of tokens per owner.
     #[ink(message)]
-    fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error>;
+    fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> PSP34Result;
 
+    /// Transfer the `id` token from `from`, which must be its owner, to `to`.
+    /// The caller must be the owner or approved by them.
+    ///
+    /// On success a `Transfer` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransfersFrozen` error if transfers are frozen.
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `IncorrectOwner` error if `from` isn't the owner of `id`.
+    ///
+    /// Returns `Soulbound` error if `id` is soulbound.
+    ///
+    /// Returns `TokenLocked` error if `id` can't be transferred yet.
+    ///
+    /// Returns `NotApproved` error if the caller doesn't have allowance for transferring.
+    ///
+    /// Returns `BelowMinBalance` error if `from` would hold less tokens than
+    /// the collection requires owners to keep.
+    ///
+    /// Returns `SafeTransferCheckFailed` error if `to` doesn't accept transfer.
+    ///
+    /// Returns `BalanceCapExceeded` error if `to` already holds the maximum
+    /// amount of tokens per owner.
     #[ink(message)]
     fn transfer_from(
         &mut self,
@@ -66,33 +128,223 @@ pub trait PSP34 {
         to: AccountId,
         id: Id,
         data: Vec<u8>,
-    ) -> Result<(), PSP34Error>;
+    ) -> PSP34Result;
 
     /// Returns the current total supply of the NFT.
     #[ink(message)]
     fn total_supply(&self) -> Balance;
 }
 
+#[ink::trait_definition]
+pub trait PSP34Interop {
+    /// Returns `true` if the operator is approved by the owner to withdraw all owner's tokens.
+    ///
+    /// Same as calling `allowance(owner, operator, None)`.
+    #[ink(message)]
+    fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool;
+
+    /// Returns the operator approved to withdraw the `id` token.
+    ///
+    /// Returns `None` if the token has no per-token approval or more than one.
+    #[ink(message)]
+    fn get_approved(&self, id: Id) -> Option<AccountId>;
+
+    /// Returns the approval of `operator` for all the tokens of `owner`,
+    /// along with when it expires.
+    #[ink(message)]
+    fn approval_status(&self, owner: AccountId, operator: AccountId) -> ApprovalStatus;
+
+    /// Approves or disapproves `operator` for the `id` token of the caller.
+    ///
+    /// Same as calling `approve(operator, Some(id), approved)`.
+    #[ink(message)]
+    fn approve_token(&mut self, operator: AccountId, id: Id, approved: bool) -> PSP34Result;
+
+    /// Approves or disapproves `operator` for all the tokens of the caller.
+    ///
+    /// Same as calling `approve(operator, None, approved)`.
+    #[ink(message)]
+    fn approve_all(&mut self, operator: AccountId, approved: bool) -> PSP34Result;
+
+    /// Returns all the operators `owner` approved for the `id` token.
+    ///
+    /// Operators approved for all of `owner`'s tokens are not included.
+    #[ink(message)]
+    fn operators_for_token(&self, owner: AccountId, id: Id) -> Vec<AccountId>;
+}
+
 #[ink::trait_definition]
 pub trait PSP34Mintable {
-    /// Mints a new token to collection.
+    /// Mints a new token to collection, returning its id.
     #[ink(message)]
-    fn mint(&mut self, account: AccountId) -> Result<(), PSP34Error>;
+    fn mint(&mut self, account: AccountId) -> PSP34Result<Id>;
 
-    /// Mints a new token to with attributes.
+    /// Mints a new token to with attributes, returning its id.
+    ///
+    /// Fails with `BatchTooLarge` if more than `max_batch_size` attributes,
+    /// the default ones included, would be set.
     #[ink(message)]
     fn mint_with_attributes(
         &mut self,
         account: AccountId,
         attributes: Vec<(Vec<u8>, Vec<u8>)>,
-    ) -> Result<(), PSP34Error>;
+    ) -> PSP34Result<Id>;
+
+    /// Mints a new token with `uri` as its explicit metadata URI, which
+    /// `token_uri` returns instead of deriving one, returning its id.
+    #[ink(message)]
+    fn mint_with_uri(&mut self, account: AccountId, uri: Vec<u8>) -> PSP34Result<Id>;
+
+    /// Returns the `Id` the next mint will assign, e.g. to pre-compute
+    /// its metadata URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoFreeId` error if too many of the upcoming ids were
+    /// already taken by explicit-id mints.
+    #[ink(message)]
+    fn next_token_id(&self) -> PSP34Result<Id>;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Capped {
+    /// Returns the maximum amount of tokens that can exist.
+    ///
+    /// Returns `Balance::MAX` if the supply is unlimited.
+    #[ink(message)]
+    fn cap(&self) -> Balance;
+
+    /// Returns how many tokens can still be minted, i.e. the cap minus the
+    /// total supply.
+    ///
+    /// Returns `Balance::MAX` if the supply is unlimited.
+    #[ink(message)]
+    fn remaining_supply(&self) -> Balance;
+
+    /// Returns `true` if the whole capped supply was minted.
+    ///
+    /// Returns `false` if the supply is unlimited.
+    #[ink(message)]
+    fn is_sold_out(&self) -> bool;
 }
 
 #[ink::trait_definition]
 pub trait PSP34Burnable {
     /// Burns a token with 'id' from account in collection.
+    ///
+    /// A `Transfer` event to `None` is emitted, followed by a `Burn` event.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotApproved` error if the caller is neither `account` nor
+    /// one of its operators for the token.
+    ///
+    /// Returns `IncorrectOwner` error if `account` doesn't own `id`.
     #[ink(message)]
-    fn burn(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error>;
+    fn burn(&mut self, account: AccountId, id: Id) -> PSP34Result;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Wrapper {
+    /// Returns the address of the wrapped PSP34 collection.
+    #[ink(message)]
+    fn underlying(&self) -> AccountId;
+
+    /// Transfers the `id` token of the underlying collection from `account`
+    /// to the contract and mints its wrapped counterpart to `account`.
+    ///
+    /// The contract must be approved for `id` on the underlying collection.
+    #[ink(message)]
+    fn deposit_for(&mut self, account: AccountId, id: Id) -> PSP34Result;
+
+    /// Burns the caller's wrapped `id` token and transfers its underlying
+    /// counterpart to `account`.
+    #[ink(message)]
+    fn withdraw_to(&mut self, account: AccountId, id: Id) -> PSP34Result;
+}
+
+/// Historical balances, e.g. for governance or airdrops based on holdings.
+///
+/// Every snapshot may cost one extra storage entry per account whose
+/// balance changes after it was taken, so take snapshots sparingly.
+#[ink::trait_definition]
+pub trait PSP34Snapshot {
+    /// Takes a snapshot of the balances and total supply, returning its id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if the caller isn't allowed to take snapshots.
+    #[ink(message)]
+    fn snapshot(&mut self) -> PSP34Result<u32>;
+
+    /// Returns the balance of `account` at the time `snapshot_id` was taken.
+    #[ink(message)]
+    fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> PSP34Result<u32>;
+
+    /// Returns the total supply at the time `snapshot_id` was taken.
+    #[ink(message)]
+    fn total_supply_at(&self, snapshot_id: u32) -> PSP34Result<Balance>;
+}
+
+#[ink::trait_definition]
+pub trait PSP34TransferWithAttribute {
+    /// Transfers `id` token from the caller to the `to` AccountId and sets
+    /// its `key` attribute to `value`, in the same call.
+    ///
+    /// A `Transfer` event is emitted, followed by an `AttributeSet` event.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransferMayBurn` error if transfers may burn the token.
+    ///
+    /// Returns any error of `PSP34::transfer`, in which case the attribute
+    /// isn't written.
+    #[ink(message)]
+    fn transfer_with_attribute(
+        &mut self,
+        to: AccountId,
+        id: Id,
+        data: Vec<u8>,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> PSP34Result;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Votes {
+    /// Returns the voting power of `account`: its balance unless it
+    /// delegated it, plus the balances delegated to it.
+    #[ink(message)]
+    fn get_votes(&self, account: AccountId) -> u128;
+
+    /// Returns the account `account` delegated its votes to, if any.
+    #[ink(message)]
+    fn delegates(&self, account: AccountId) -> Option<AccountId>;
+
+    /// Delegates the caller's votes to `to`, including the tokens it
+    /// receives later on.
+    ///
+    /// A `DelegateChanged` event is emitted, followed by
+    /// `DelegateVotesChanged` events for the delegates whose votes changed.
+    #[ink(message)]
+    fn delegate(&mut self, to: AccountId) -> PSP34Result;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Swap {
+    /// Burns the caller's `old_id` token and mints them a new one with
+    /// `new_attributes` atomically.
+    ///
+    /// `Transfer` events are emitted for the burn and the mint, plus an
+    /// `AttributeSet` event per attribute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `old_id` does not exist.
+    ///
+    /// Returns `NotApproved` error if the caller isn't the owner of `old_id`.
+    #[ink(message)]
+    fn swap(&mut self, old_id: Id, new_attributes: Vec<(Vec<u8>, Vec<u8>)>) -> PSP34Result;
 }
 
 #[ink::trait_definition]
@@ -106,6 +358,23 @@ pub trait PSP34Enumerable {
     /// Use along with `total_supply` to enumerate all tokens.
     #[ink(message)]
     fn token_by_index(&self, index: u128) -> Option<Id>;
+
+    /// Returns the first existing token at or after `from_index`, along
+    /// with its index, skipping the `None` slots `token_by_index` returns
+    /// for burnt tokens in stable enumeration mode.
+    #[ink(message)]
+    fn next_existing_token(&self, from_index: u128) -> Option<(u128, Id)>;
+
+    /// Returns the index of the `id` token in the list of all tokens,
+    /// the reverse of `token_by_index`. Returns `None` if `id` wasn't minted.
+    #[ink(message)]
+    fn index_of_token(&self, id: Id) -> Option<u128>;
+
+    /// Returns the index of the `id` token in the token list of `owner`,
+    /// the reverse of `owners_token_by_index`. Returns `None` if `owner`
+    /// doesn't own `id`.
+    #[ink(message)]
+    fn index_of_owner_token(&self, owner: AccountId, id: Id) -> Option<u128>;
 }
 
 #[ink::trait_definition]
@@ -113,4 +382,131 @@ pub trait PSP34Metadata {
     /// Returns the attribute of `id` for the given `key`.
     #[ink(message)]
     fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>>;
+
+    /// Returns the attribute of `id` for the given `key`, `None` if the
+    /// token exists but lacks the attribute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    #[ink(message)]
+    fn try_get_attribute(&self, id: Id, key: Vec<u8>) -> PSP34Result<Option<Vec<u8>>>;
+
+    /// Returns the attribute of `id` for the given `key` stored in chunks,
+    /// concatenated back into a single value.
+    #[ink(message)]
+    fn get_attribute_full(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>>;
+
+    /// Returns every attribute of `id` as key/value pairs, up to a bound
+    /// set by the collection. Collections holding more attributes per
+    /// token than the bound should expose a paginated read as well.
+    #[ink(message)]
+    fn all_attributes(&self, id: Id) -> Vec<(Vec<u8>, Vec<u8>)>;
+
+    /// Returns the attribute of each of the `ids` for the given `key`,
+    /// positionally aligned with them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BatchTooLarge` error if too many ids are given.
+    #[ink(message)]
+    fn get_attribute_for_tokens(
+        &self,
+        ids: Vec<Id>,
+        key: Vec<u8>,
+    ) -> PSP34Result<Vec<Option<Vec<u8>>>>;
+}
+
+/// Tamper-evidence for off-chain collection metadata, e.g. a JSON file,
+/// without storing it on-chain.
+#[ink::trait_definition]
+pub trait PSP34MetadataHash {
+    /// Returns the Blake2x256 hash of the collection metadata, if set.
+    #[ink(message)]
+    fn collection_metadata_hash(&self) -> Option<[u8; 32]>;
+
+    /// Sets the hash of the collection metadata, can only be done once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if the hash was already set or the caller
+    /// isn't allowed to set it.
+    #[ink(message)]
+    fn set_collection_metadata_hash(&mut self, hash: [u8; 32]) -> PSP34Result;
+
+    /// Returns `true` if `preimage` hashes to the collection metadata hash.
+    #[ink(message)]
+    fn verify_metadata(&self, preimage: Vec<u8>) -> bool;
+}
+
+#[ink::trait_definition]
+pub trait PSP34MutableMetadata {
+    /// Sets the attribute of `id` for the given `key` to `value`.
+    ///
+    /// An `AttributeSet` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `NotApproved` error if the caller isn't the owner of `id` nor approved.
+    ///
+    /// Returns `MetadataFrozen` error if the attributes of `id` are frozen.
+    #[ink(message)]
+    fn set_attribute(&mut self, id: Id, key: Vec<u8>, value: Vec<u8>) -> PSP34Result;
+
+    /// Sets all the `attributes` of `id` at once, replacing existing keys.
+    ///
+    /// An `AttributeSet` event is emitted per attribute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `NotApproved` error if the caller isn't the owner of `id` nor approved.
+    ///
+    /// Returns `MetadataFrozen` error if the attributes of `id` are frozen.
+    ///
+    /// Returns `BatchTooLarge` error if too many attributes are given.
+    #[ink(message)]
+    fn set_attributes(&mut self, id: Id, attributes: Vec<(Vec<u8>, Vec<u8>)>) -> PSP34Result;
+
+    /// Removes the attribute of `id` for the given `key`.
+    ///
+    /// An `AttributeSet` event with empty `data` is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` does not exist.
+    ///
+    /// Returns `NotApproved` error if the caller isn't the owner of `id` nor approved.
+    ///
+    /// Returns `MetadataFrozen` error if the attributes of `id` are frozen.
+    #[ink(message)]
+    fn remove_attribute(&mut self, id: Id, key: Vec<u8>) -> PSP34Result;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Introspection {
+    /// Returns `true` if the contract implements the interface identified
+    /// by `interface_id`, see the `interfaces` module for the known ids.
+    #[ink(message)]
+    fn supports_interface(&self, interface_id: u32) -> bool;
+}
+
+/// Implemented by contracts willing to receive PSP34 tokens through
+/// `PSP34::transfer`.
+#[ink::trait_definition]
+pub trait PSP34Receiver {
+    /// Called by the collection after `id` was transferred from `from` to
+    /// this contract on behalf of `operator`, with the `data` given to the
+    /// transfer. Returning an error reverts the transfer.
+    #[ink(message)]
+    fn on_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        id: Id,
+        data: Vec<u8>,
+    ) -> PSP34Result;
 }
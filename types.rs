@@ -1,5 +1,10 @@
+use core::cmp::Ordering;
+
 use ink::env::{DefaultEnvironment, Environment};
 use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+
+use crate::{PSP34Error, PSP34Result, WellKnownKeys};
 
 /// Id is an Enum of its variants and types
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, scale::Encode, scale::Decode)]
@@ -16,6 +21,56 @@ pub enum Id {
     Bytes(Vec<u8>),
 }
 
+/// Variant of an `Id`, see `Id::from_u128_as`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum IdKind {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Bytes,
+}
+
+impl Id {
+    /// Builds an `Id` of the requested `kind` from `value`.
+    ///
+    /// Converting a numeric `Id` to `u128` always widens it and can't lose
+    /// information, whereas the other way around has no single right
+    /// variant: this crate never picks one implicitly, callers state the
+    /// variant they expect instead. A `Bytes` id holds the 16 big-endian
+    /// bytes of `value`, mirroring the conversion to `u128`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidId` error if `value` doesn't fit in `kind`, rather
+    /// than truncating it.
+    pub fn from_u128_as(value: u128, kind: IdKind) -> PSP34Result<Id> {
+        let invalid = |_| PSP34Error::InvalidId;
+        Ok(match kind {
+            IdKind::U8 => Id::U8(value.try_into().map_err(invalid)?),
+            IdKind::U16 => Id::U16(value.try_into().map_err(invalid)?),
+            IdKind::U32 => Id::U32(value.try_into().map_err(invalid)?),
+            IdKind::U64 => Id::U64(value.try_into().map_err(invalid)?),
+            IdKind::U128 => Id::U128(value),
+            IdKind::Bytes => Id::Bytes(value.to_be_bytes().to_vec()),
+        })
+    }
+
+    /// Compares ids by their numeric value, so that `Id::U8(200)` sorts
+    /// after `Id::U16(1)`, unlike the derived `Ord` which compares the
+    /// variants first. `Bytes` ids compare lexicographically among
+    /// themselves, `None` is returned when comparing them to numeric ids.
+    pub fn numeric_cmp(&self, other: &Id) -> Option<Ordering> {
+        match (self, other) {
+            (Id::Bytes(a), Id::Bytes(b)) => Some(a.cmp(b)),
+            (Id::Bytes(_), _) | (_, Id::Bytes(_)) => None,
+            (a, b) => Some(u128::from(a.clone()).cmp(&u128::from(b.clone()))),
+        }
+    }
+}
+
 impl From<Id> for u128 {
     fn from(id: Id) -> Self {
         match id {
@@ -29,4 +84,247 @@ impl From<Id> for u128 {
     }
 }
 
+/// Approval of an operator for all the tokens of an owner
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ApprovalStatus {
+    /// Whether the operator is approved for all the owner's tokens
+    pub all: bool,
+    /// Block number after which the approval lapses, `None` if it never
+    /// does
+    pub expiry: Option<u64>,
+}
+
+/// Aggregate of the details of a minted token, returned by `token_info`
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct TokenInfo {
+    /// Owner of the token
+    pub owner: AccountId,
+    /// Whether the token exists, always `true` for a returned value
+    pub exists: bool,
+    /// Operators approved for this token by its owner, at most
+    /// `max_batch_size` of them
+    pub approved_operators: Vec<AccountId>,
+    /// Amount of operators approved for this token by its owner
+    pub operator_count: u32,
+    /// Keys of the attributes set on this token, at most `max_batch_size`
+    /// of them, the rest being paged through with `attributes_from`
+    pub attribute_keys: Vec<Vec<u8>>,
+    /// Amount of attributes set on this token
+    pub attribute_count: u32,
+}
+
+/// Settings of a new collection, the ones left to `None` keeping the
+/// defaults of `PSP34Data::new`
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct PSP34Config {
+    /// Id of the collection, derived from the contract's account id
+    pub collection_id: Option<Id>,
+    /// Maximum amount of tokens that can exist
+    pub max_supply: Option<Balance>,
+    /// Price of a paid mint
+    pub mint_price: Option<Balance>,
+    /// Maximum amount of items accepted by batch operations
+    pub max_batch_size: Option<u32>,
+    /// Owner of the contract, the deployer if `None`
+    pub owner: Option<AccountId>,
+    /// Keys of the well-known attributes, the conventional ones if `None`
+    pub well_known_keys: Option<WellKnownKeys>,
+    /// Account receiving the creator cut of `transfer_paid` sales
+    pub royalty_recipient: Option<AccountId>,
+    /// Creator cut of `transfer_paid` sales, in basis points
+    pub royalty_bps: Option<u16>,
+    /// Minimum amount of blocks between two mints of an account
+    pub mint_cooldown_blocks: Option<u64>,
+}
+
+impl PSP34Config {
+    /// Returns a builder of a config, validating it once built.
+    pub fn builder() -> PSP34ConfigBuilder {
+        PSP34ConfigBuilder::default()
+    }
+}
+
+/// Builder of a `PSP34Config`, see `PSP34Config::builder`
+#[derive(Debug, Default, Clone)]
+pub struct PSP34ConfigBuilder {
+    config: PSP34Config,
+}
+
+impl PSP34ConfigBuilder {
+    pub fn collection_id(mut self, collection_id: Id) -> Self {
+        self.config.collection_id = Some(collection_id);
+        self
+    }
+
+    pub fn max_supply(mut self, max_supply: Balance) -> Self {
+        self.config.max_supply = Some(max_supply);
+        self
+    }
+
+    pub fn mint_price(mut self, mint_price: Balance) -> Self {
+        self.config.mint_price = Some(mint_price);
+        self
+    }
+
+    pub fn max_batch_size(mut self, max_batch_size: u32) -> Self {
+        self.config.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    pub fn owner(mut self, owner: AccountId) -> Self {
+        self.config.owner = Some(owner);
+        self
+    }
+
+    pub fn well_known_keys(mut self, well_known_keys: WellKnownKeys) -> Self {
+        self.config.well_known_keys = Some(well_known_keys);
+        self
+    }
+
+    pub fn royalty_recipient(mut self, royalty_recipient: AccountId) -> Self {
+        self.config.royalty_recipient = Some(royalty_recipient);
+        self
+    }
+
+    pub fn royalty_bps(mut self, royalty_bps: u16) -> Self {
+        self.config.royalty_bps = Some(royalty_bps);
+        self
+    }
+
+    /// Sets the minimum amount of blocks between two mints of an account.
+    ///
+    /// Left unbounded by `build` as every value is meaningful: 0 disables
+    /// the cooldown, while a cooldown outlasting the mint window limits
+    /// each account to a single mint.
+    pub fn mint_cooldown_blocks(mut self, mint_cooldown_blocks: u64) -> Self {
+        self.config.mint_cooldown_blocks = Some(mint_cooldown_blocks);
+        self
+    }
+
+    /// Returns the config, once checked for settings that would leave the
+    /// collection unusable or misbehaving.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Custom` error if `royalty_bps` is above 10000, or set
+    /// without a `royalty_recipient`.
+    ///
+    /// Returns `ReachedMaxSupply` error if `max_supply` is 0, which would
+    /// prevent any mint.
+    ///
+    /// Returns `BatchTooLarge` error if `max_batch_size` is 0, which would
+    /// prevent any batch operation.
+    pub fn build(self) -> PSP34Result<PSP34Config> {
+        let config = self.config;
+
+        if config.royalty_bps.is_some_and(|bps| bps > 10_000) {
+            return Err(PSP34Error::Custom("royalty above 100%".into()));
+        }
+
+        if config.royalty_bps.is_some_and(|bps| bps > 0) && config.royalty_recipient.is_none() {
+            return Err(PSP34Error::Custom("royalty without recipient".into()));
+        }
+
+        if config.max_supply == Some(0) {
+            return Err(PSP34Error::ReachedMaxSupply);
+        }
+
+        if config.max_batch_size == Some(0) {
+            return Err(PSP34Error::BatchTooLarge);
+        }
+
+        Ok(config)
+    }
+}
+
 pub type Balance = <DefaultEnvironment as Environment>::Balance;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scale::Encode;
+
+    #[test]
+    fn build_rejects_a_royalty_above_100_percent() {
+        let recipient = AccountId::from([1; 32]);
+
+        assert!(PSP34Config::builder()
+            .royalty_recipient(recipient)
+            .royalty_bps(10_001)
+            .build()
+            .is_err());
+        assert!(PSP34Config::builder()
+            .royalty_recipient(recipient)
+            .royalty_bps(10_000)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn build_rejects_a_royalty_without_recipient() {
+        assert!(PSP34Config::builder().royalty_bps(500).build().is_err());
+        assert!(PSP34Config::builder().royalty_bps(0).build().is_ok());
+    }
+
+    #[test]
+    fn build_rejects_zero_limits() {
+        assert_eq!(
+            PSP34Config::builder().max_supply(0).build(),
+            Err(PSP34Error::ReachedMaxSupply)
+        );
+        assert_eq!(
+            PSP34Config::builder().max_batch_size(0).build(),
+            Err(PSP34Error::BatchTooLarge)
+        );
+    }
+
+    #[test]
+    fn build_accepts_any_mint_cooldown() {
+        let config = PSP34Config::builder()
+            .mint_cooldown_blocks(u64::MAX)
+            .build()
+            .unwrap();
+        assert_eq!(config.mint_cooldown_blocks, Some(u64::MAX));
+    }
+
+    #[test]
+    fn numeric_ids_encode_to_their_width_plus_a_tag() {
+        assert_eq!(Id::U8(1).encode().len(), 2);
+        assert_eq!(Id::U16(1).encode().len(), 3);
+        assert_eq!(Id::U32(1).encode().len(), 5);
+        assert_eq!(Id::U64(1).encode().len(), 9);
+        assert_eq!(Id::U128(1).encode().len(), 17);
+        // tag, compact length and the bytes themselves
+        assert_eq!(Id::Bytes(vec![1; 32]).encode().len(), 34);
+        assert_ne!(Id::U8(1).encode(), Id::U128(1).encode());
+    }
+
+    #[test]
+    fn numeric_cmp_compares_values_across_variants() {
+        assert_eq!(
+            Id::U8(200).numeric_cmp(&Id::U16(1)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(Id::U16(1).numeric_cmp(&Id::U8(200)), Some(Ordering::Less));
+        assert_eq!(Id::U8(1).numeric_cmp(&Id::U128(1)), Some(Ordering::Equal));
+        // the derived order compares the variants first
+        assert!(Id::U8(200) < Id::U16(1));
+        assert_eq!(Id::U8(1).numeric_cmp(&Id::Bytes(vec![1])), None);
+    }
+
+    #[test]
+    fn from_u128_as_rejects_values_too_wide_for_the_kind() {
+        assert_eq!(
+            Id::from_u128_as(300, IdKind::U8),
+            Err(PSP34Error::InvalidId)
+        );
+        assert_eq!(Id::from_u128_as(300, IdKind::U16), Ok(Id::U16(300)));
+        assert_eq!(Id::from_u128_as(300, IdKind::U128), Ok(Id::U128(300)));
+
+        let bytes = Id::from_u128_as(300, IdKind::Bytes).unwrap();
+        assert_eq!(u128::from(bytes), 300);
+    }
+}
@@ -0,0 +1,306 @@
+use ink::codegen::TraitCallBuilder;
+use ink::env::{CallFlags, DefaultEnvironment};
+use ink::{contract_ref, prelude::vec::Vec, primitives::AccountId};
+
+use crate::{Id, PSP34Data, PSP34Error, PSP34Event, PSP34Result, PSP34};
+#[cfg(feature = "client")]
+use crate::{PSP34CallError, PSP34Ref};
+
+/// Calls a wrapper makes to its underlying collection, abstracted so that
+/// the deposit and withdraw logic can run against a mock in tests.
+pub trait PSP34Underlying {
+    /// Moves the `id` token from `from` to `to` on behalf of the wrapper.
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, id: Id) -> PSP34Result;
+
+    /// Moves the `id` token held by the wrapper to `to`.
+    fn transfer(&mut self, to: AccountId, id: Id) -> PSP34Result;
+}
+
+/// Calls the collection deployed at this address through `contract_ref!`.
+///
+/// `transfer_from` allows the collection to call back into the wrapper, as
+/// collections calling `PSP34Receiver::on_received` do when the wrapper
+/// pulls a token into itself.
+impl PSP34Underlying for AccountId {
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, id: Id) -> PSP34Result {
+        let mut underlying: contract_ref!(PSP34, DefaultEnvironment) = (*self).into();
+        underlying
+            .call_mut()
+            .transfer_from(from, to, id, Vec::new())
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .invoke()
+    }
+
+    fn transfer(&mut self, to: AccountId, id: Id) -> PSP34Result {
+        let mut underlying: contract_ref!(PSP34, DefaultEnvironment) = (*self).into();
+        underlying.transfer(to, id, Vec::new())
+    }
+}
+
+/// Calls the collection through `PSP34Ref`, failing instead of trapping
+/// if the call itself fails.
+#[cfg(feature = "client")]
+impl PSP34Underlying for PSP34Ref {
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, id: Id) -> PSP34Result {
+        PSP34Ref::transfer_from(self, from, to, id, Vec::new()).map_err(underlying_error)
+    }
+
+    fn transfer(&mut self, to: AccountId, id: Id) -> PSP34Result {
+        PSP34Ref::transfer(self, to, id, Vec::new()).map_err(underlying_error)
+    }
+}
+
+#[cfg(feature = "client")]
+fn underlying_error(error: PSP34CallError) -> PSP34Error {
+    match error {
+        PSP34CallError::PSP34(error) => error,
+        _ => PSP34Error::Custom("underlying call failed".into()),
+    }
+}
+
+/// Storage and logic for a contract wrapping the tokens of an existing
+/// PSP34 collection (the `underlying` one) into its own, 1:1 and under
+/// the same `Id`s.
+///
+/// The underlying tokens are held by the wrapper contract for as long
+/// as their wrapped counterpart exists.
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct PSP34WrapperData {
+    /// Address of the wrapped PSP34 collection
+    pub underlying: AccountId,
+}
+
+impl PSP34WrapperData {
+    pub fn new(underlying: AccountId) -> PSP34WrapperData {
+        PSP34WrapperData { underlying }
+    }
+
+    pub fn underlying(&self) -> AccountId {
+        self.underlying
+    }
+
+    /// Same as `deposit_for_with`, calling the `underlying` collection
+    /// through `contract_ref!`.
+    ///
+    /// The call allows reentry, so that collections calling back
+    /// `PSP34Receiver::on_received` on `contract` don't fail the deposit.
+    /// Off-chain tests can't invoke contracts, so the mock-based tests of
+    /// this module don't cover it.
+    pub fn deposit_for(
+        &mut self,
+        data: &mut PSP34Data,
+        contract: AccountId,
+        account: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let mut underlying = self.underlying;
+        self.deposit_for_with(&mut underlying, data, contract, account, id)
+    }
+
+    /// Pulls the `id` token of the `underlying` collection from `account`
+    /// into `contract` (the wrapper's own address) and mints its wrapped
+    /// counterpart to `account`.
+    ///
+    /// The wrapper contract must be approved by `account` on the
+    /// underlying collection beforehand.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by the underlying `transfer_from`.
+    ///
+    /// Returns `TokenExists` error if `id` is already wrapped.
+    pub fn deposit_for_with<U: PSP34Underlying>(
+        &mut self,
+        underlying: &mut U,
+        data: &mut PSP34Data,
+        contract: AccountId,
+        account: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        underlying.transfer_from(account, contract, id.clone())?;
+
+        data.mint_with_id(account, id, Vec::new())
+    }
+
+    /// Same as `withdraw_to_with`, calling the `underlying` collection
+    /// through `contract_ref!`.
+    pub fn withdraw_to(
+        &mut self,
+        data: &mut PSP34Data,
+        caller: AccountId,
+        account: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let mut underlying = self.underlying;
+        self.withdraw_to_with(&mut underlying, data, caller, account, id)
+    }
+
+    /// Burns the wrapped `id` token owned by `caller` and sends its
+    /// counterpart of the `underlying` collection to `account`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TokenNotExists` error if `id` is not wrapped.
+    ///
+    /// Returns `NotApproved` error if `caller` doesn't own `id`.
+    ///
+    /// Returns any error raised by the underlying `transfer`.
+    pub fn withdraw_to_with<U: PSP34Underlying>(
+        &mut self,
+        underlying: &mut U,
+        data: &mut PSP34Data,
+        caller: AccountId,
+        account: AccountId,
+        id: Id,
+    ) -> PSP34Result<Vec<PSP34Event>> {
+        let owner = data
+            .owner_of(id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+
+        if owner != caller {
+            return Err(PSP34Error::NotApproved);
+        }
+
+        let events = data.burn(owner, id.clone())?;
+
+        underlying.transfer(account, id)?;
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn account(n: u8) -> AccountId {
+        AccountId::from([n; 32])
+    }
+
+    /// Address of the wrapper contract
+    fn wrapper_account() -> AccountId {
+        account(9)
+    }
+
+    /// Underlying collection keeping its owners in memory, whose
+    /// `transfer_from` trusts the wrapper to be approved
+    #[derive(Default)]
+    struct MockUnderlying {
+        owners: BTreeMap<Id, AccountId>,
+    }
+
+    impl PSP34Underlying for MockUnderlying {
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, id: Id) -> PSP34Result {
+            match self.owners.get(&id) {
+                None => Err(PSP34Error::TokenNotExists),
+                Some(owner) if *owner != from => Err(PSP34Error::IncorrectOwner),
+                _ => {
+                    self.owners.insert(id, to);
+                    Ok(())
+                }
+            }
+        }
+
+        fn transfer(&mut self, to: AccountId, id: Id) -> PSP34Result {
+            PSP34Underlying::transfer_from(self, wrapper_account(), to, id)
+        }
+    }
+
+    fn setup() -> (PSP34WrapperData, PSP34Data, MockUnderlying) {
+        let mut underlying = MockUnderlying::default();
+        underlying.owners.insert(Id::U128(0), account(1));
+        (
+            PSP34WrapperData::new(account(0)),
+            PSP34Data::new(),
+            underlying,
+        )
+    }
+
+    #[ink::test]
+    fn deposit_pulls_the_token_and_mints_it_wrapped() {
+        let (mut wrapper, mut data, mut underlying) = setup();
+        let alice = account(1);
+
+        wrapper
+            .deposit_for_with(
+                &mut underlying,
+                &mut data,
+                wrapper_account(),
+                alice,
+                Id::U128(0),
+            )
+            .unwrap();
+
+        assert_eq!(
+            underlying.owners.get(&Id::U128(0)),
+            Some(&wrapper_account())
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+    }
+
+    #[ink::test]
+    fn deposit_of_a_token_of_someone_else_fails() {
+        let (mut wrapper, mut data, mut underlying) = setup();
+
+        assert_eq!(
+            wrapper.deposit_for_with(
+                &mut underlying,
+                &mut data,
+                wrapper_account(),
+                account(2),
+                Id::U128(0)
+            ),
+            Err(PSP34Error::IncorrectOwner)
+        );
+        assert_eq!(data.total_supply(), 0);
+    }
+
+    #[ink::test]
+    fn withdraw_burns_the_wrapped_token_and_returns_the_underlying() {
+        let (mut wrapper, mut data, mut underlying) = setup();
+        let (alice, bob) = (account(1), account(2));
+        wrapper
+            .deposit_for_with(
+                &mut underlying,
+                &mut data,
+                wrapper_account(),
+                alice,
+                Id::U128(0),
+            )
+            .unwrap();
+
+        wrapper
+            .withdraw_to_with(&mut underlying, &mut data, alice, bob, Id::U128(0))
+            .unwrap();
+
+        assert_eq!(data.owner_of(Id::U128(0)), None);
+        assert_eq!(underlying.owners.get(&Id::U128(0)), Some(&bob));
+    }
+
+    #[ink::test]
+    fn withdraw_by_someone_else_fails() {
+        let (mut wrapper, mut data, mut underlying) = setup();
+        let (alice, bob) = (account(1), account(2));
+        wrapper
+            .deposit_for_with(
+                &mut underlying,
+                &mut data,
+                wrapper_account(),
+                alice,
+                Id::U128(0),
+            )
+            .unwrap();
+
+        assert_eq!(
+            wrapper.withdraw_to_with(&mut underlying, &mut data, bob, bob, Id::U128(0)),
+            Err(PSP34Error::NotApproved)
+        );
+        assert_eq!(data.owner_of(Id::U128(0)), Some(alice));
+        assert_eq!(
+            underlying.owners.get(&Id::U128(0)),
+            Some(&wrapper_account())
+        );
+    }
+}